@@ -0,0 +1,82 @@
+//! Compares writing every format section on its own with rendering the line once.
+//! Writes go to the null device so each `write_all` is a real syscall
+use std::fs::File;
+use std::io::Write;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use log::{Level, Log, Record};
+use nitro_log::config::{create_loggers, FormatConfig};
+use nitro_log::format::Format;
+use nitro_log::loggers::tree::LoggerTree;
+use nitro_log::{LoggerBuilders, NitroLogger};
+use serde_json::json;
+
+const FORMAT: &str =
+    "[{{level({}):>5}}] {{module({})}} {{#if user}}user={{user}} {{/if}}{{message({})}}";
+
+#[cfg(windows)]
+const NULL: &str = "NUL";
+#[cfg(not(windows))]
+const NULL: &str = "/dev/null";
+
+fn record_with<R>(f: impl FnOnce(&Record) -> R) -> R {
+    let kvs = [("user", "admin"), ("request", "42")];
+    f(&Record::builder()
+        .args(format_args!("Handled the request in {}ms", 12))
+        .level(Level::Info)
+        .target("my_app::server")
+        .module_path(Some("my_app::server::handler"))
+        .key_values(&kvs)
+        .build())
+}
+
+fn write(c: &mut Criterion) {
+    let builders = LoggerBuilders::default();
+    let format = Format::new(
+        &builders.placeholders,
+        FormatConfig::from(FORMAT.to_string()),
+        false,
+    )
+    .unwrap();
+    let mut file = File::create(NULL).unwrap();
+    let mut group = c.benchmark_group("write");
+    record_with(|record| {
+        group.bench_function("per_section", |b| {
+            b.iter(|| {
+                for section in &format.format {
+                    file.write_all(section.build_message(record).as_bytes())
+                        .unwrap();
+                }
+                file.write_all(b"\n").unwrap();
+            })
+        });
+        let mut line = String::new();
+        group.bench_function("single_write", |b| {
+            b.iter(|| {
+                line.clear();
+                format.render_into(record, &mut line);
+                line.push('\n');
+                file.write_all(line.as_bytes()).unwrap();
+            })
+        });
+    });
+    group.finish();
+}
+
+fn log(c: &mut Criterion) {
+    let config = json!({
+        "root_loggers": [{
+            "format": FORMAT,
+            "structure_dump": true,
+            "targets": [{ "type": "file_logger", "properties": { "file": NULL } }]
+        }]
+    });
+    let builders = LoggerBuilders::default();
+    let (root, loggers) =
+        create_loggers(serde_json::from_value(config).unwrap(), &builders).unwrap();
+    let logger = NitroLogger::new(LoggerTree::new(root, loggers), Box::new(|_| {}));
+    record_with(|record| c.bench_function("log", |b| b.iter(|| logger.log(record))));
+}
+
+criterion_group!(benches, write, log);
+criterion_main!(benches);
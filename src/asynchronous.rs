@@ -0,0 +1,255 @@
+use std::cell::Cell;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Condvar, Mutex, MutexGuard};
+use std::time::SystemTime;
+
+use log::{Level, Record};
+use serde::{Deserialize, Serialize};
+
+use crate::kv::owned::OwnedKeyValues;
+use crate::placeholder::with_record_time;
+
+/// Moves formatting and writing onto a background thread
+///
+/// ```json
+/// "async": { "capacity": 1024, "overflow": "drop_oldest" }
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AsyncConfig {
+    /// How many records can wait for the writer thread
+    #[serde(default = "default_capacity")]
+    pub capacity: usize,
+    /// What to do when the queue is full
+    #[serde(default)]
+    pub overflow: OverflowPolicy,
+}
+
+fn default_capacity() -> usize {
+    1024
+}
+
+impl Default for AsyncConfig {
+    fn default() -> Self {
+        AsyncConfig {
+            capacity: default_capacity(),
+            overflow: OverflowPolicy::default(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum OverflowPolicy {
+    /// The logging thread waits for space.
+    /// Records logged by the writer thread itself are dropped instead. Only it can make space
+    #[default]
+    Block,
+    /// The new record is dropped
+    DropNewest,
+    /// The oldest queued record is dropped to make room
+    DropOldest,
+}
+
+/// A copy of a Record that owns all of its data
+#[derive(Debug, Clone)]
+pub struct OwnedRecord {
+    pub level: Level,
+    pub target: String,
+    pub module_path: Option<String>,
+    pub file: Option<String>,
+    pub line: Option<u32>,
+    pub message: String,
+    pub key_values: OwnedKeyValues,
+    /// When the record was logged. Used in place of the time it is written
+    pub time: SystemTime,
+}
+
+impl From<&Record<'_>> for OwnedRecord {
+    fn from(record: &Record) -> Self {
+        OwnedRecord {
+            level: record.level(),
+            target: record.target().to_owned(),
+            module_path: record.module_path().map(str::to_owned),
+            file: record.file().map(str::to_owned),
+            line: record.line(),
+            message: record.args().to_string(),
+            key_values: OwnedKeyValues::capture(record.key_values()),
+            time: SystemTime::now(),
+        }
+    }
+}
+
+impl OwnedRecord {
+    /// Rebuilds the Record and hands it to the function. With the time it was logged as the record time
+    pub fn with_record<R>(&self, function: impl FnOnce(&Record) -> R) -> R {
+        with_record_time(self.time, || {
+            function(
+                &Record::builder()
+                    .args(format_args!("{}", self.message))
+                    .level(self.level)
+                    .target(&self.target)
+                    .module_path(self.module_path.as_deref())
+                    .file(self.file.as_deref())
+                    .line(self.line)
+                    .key_values(&self.key_values)
+                    .build(),
+            )
+        })
+    }
+}
+
+thread_local! {
+    static WRITER_THREAD: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Marks the current thread as the writer thread of the queue
+pub(crate) fn set_writer_thread() {
+    WRITER_THREAD.with(|writer| writer.set(true));
+}
+
+/// The bounded queue between the logging threads and the writer thread
+pub struct AsyncQueue {
+    pub config: AsyncConfig,
+    state: Mutex<QueueState>,
+    not_empty: Condvar,
+    not_full: Condvar,
+    drained: Condvar,
+    dropped: AtomicU64,
+}
+
+struct QueueState {
+    records: VecDeque<OwnedRecord>,
+    /// The writer thread is handling a record it popped
+    writing: bool,
+    /// The writer thread has stopped. New records are dropped instead of waiting for it
+    closed: bool,
+}
+
+impl From<AsyncConfig> for AsyncQueue {
+    fn from(config: AsyncConfig) -> Self {
+        AsyncQueue {
+            state: Mutex::new(QueueState {
+                records: VecDeque::with_capacity(config.capacity),
+                writing: false,
+                closed: false,
+            }),
+            config,
+            not_empty: Condvar::new(),
+            not_full: Condvar::new(),
+            drained: Condvar::new(),
+            dropped: AtomicU64::new(0),
+        }
+    }
+}
+
+impl AsyncQueue {
+    /// Queues the record. Applying the overflow policy if the queue is full
+    pub fn push(&self, record: OwnedRecord) {
+        let capacity = self.config.capacity.max(1);
+        let mut state = self.lock();
+        if state.closed {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+        while state.records.len() >= capacity {
+            match self.config.overflow {
+                OverflowPolicy::Block if WRITER_THREAD.with(Cell::get) => {
+                    self.dropped.fetch_add(1, Ordering::Relaxed);
+                    return;
+                }
+                OverflowPolicy::Block => {
+                    state = self
+                        .not_full
+                        .wait(state)
+                        .unwrap_or_else(|error| error.into_inner());
+                    if state.closed {
+                        self.dropped.fetch_add(1, Ordering::Relaxed);
+                        return;
+                    }
+                }
+                OverflowPolicy::DropNewest => {
+                    self.dropped.fetch_add(1, Ordering::Relaxed);
+                    return;
+                }
+                OverflowPolicy::DropOldest => {
+                    state.records.pop_front();
+                    self.dropped.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+        }
+        state.records.push_back(record);
+        self.not_empty.notify_one();
+    }
+
+    /// Waits for the next record. [AsyncQueue::finish] must be called once the record is written.
+    /// None once the queue is closed
+    pub fn pop(&self) -> Option<OwnedRecord> {
+        let mut state = self.lock();
+        loop {
+            if state.closed {
+                return None;
+            }
+            if let Some(record) = state.records.pop_front() {
+                state.writing = true;
+                self.not_full.notify_one();
+                return Some(record);
+            }
+            state = self
+                .not_empty
+                .wait(state)
+                .unwrap_or_else(|error| error.into_inner());
+        }
+    }
+
+    /// Marks the last popped record as written
+    pub fn finish(&self) {
+        let mut state = self.lock();
+        state.writing = false;
+        if state.records.is_empty() {
+            self.drained.notify_all();
+        }
+    }
+
+    /// Waits until every queued record has been written
+    pub fn wait_until_drained(&self) {
+        let mut state = self.lock();
+        while !state.closed && (state.writing || !state.records.is_empty()) {
+            state = self
+                .drained
+                .wait(state)
+                .unwrap_or_else(|error| error.into_inner());
+        }
+    }
+
+    /// Marks the writer thread as stopped. The queued records are dropped and every waiting thread is woken
+    pub fn close(&self) {
+        let mut state = self.lock();
+        state.closed = true;
+        state.writing = false;
+        let queued = state.records.len() as u64;
+        state.records.clear();
+        self.dropped.fetch_add(queued, Ordering::Relaxed);
+        self.not_empty.notify_all();
+        self.not_full.notify_all();
+        self.drained.notify_all();
+    }
+
+    /// The number of records dropped because the queue was full or the writer thread stopped
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, QueueState> {
+        self.state.lock().unwrap_or_else(|error| error.into_inner())
+    }
+}
+
+/// Closes the queue when dropped. Held by the writer thread so the queue is closed if the thread exits
+pub struct CloseOnExit<'queue>(pub &'queue AsyncQueue);
+
+impl Drop for CloseOnExit<'_> {
+    fn drop(&mut self) {
+        self.0.close();
+    }
+}
@@ -1,11 +1,13 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt;
+use std::fs::read_to_string;
 use std::marker::PhantomData;
+use std::path::Path;
 use std::str::FromStr;
-use std::vec::IntoIter;
+use std::sync::Arc;
 
-use log::Level;
 use log::Level::{Debug, Error, Info, Trace, Warn};
+use log::{Level, LevelFilter};
 use serde::{Deserialize, Deserializer, Serialize};
 
 use serde::de::value::MapAccessDeserializer;
@@ -13,16 +15,28 @@ use serde::de::{MapAccess, Visitor};
 
 use serde_json::Value;
 
+use crate::asynchronous::AsyncConfig;
+use crate::env::EnvOverrides;
+use crate::error::ValidationError;
 use crate::format::Format;
-use crate::loggers::target::LoggerTarget;
+use crate::loggers::color::ColorMode;
+use crate::loggers::encoder::Encoder;
+use crate::loggers::target::{LoggerOutput, LoggerTargetBuilder};
+use crate::loggers::tree::pattern::ModulePattern;
 use crate::{Logger, LoggerBuilders};
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct FormatConfig {
     pub format: String,
     pub placeholders: HashMap<String, Value>,
 }
 
+impl Default for FormatConfig {
+    fn default() -> Self {
+        FormatConfig::from("{{message({})}}".to_string())
+    }
+}
+
 impl FromStr for FormatConfig {
     type Err = ();
 
@@ -79,7 +93,7 @@ where
 }
 
 /// Target Config
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct TargetConfig {
     /// Target Name Ex: console or file
     #[serde(rename = "type")]
@@ -87,20 +101,111 @@ pub struct TargetConfig {
     /// Properties. Refer to Target config struct for more information
     #[serde(default)]
     pub properties: Value,
+    /// Used instead of the format of the logger
+    #[serde(
+        default,
+        deserialize_with = "optional_format_config",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub format: Option<FormatConfig>,
+    /// Used instead of the levels of the logger
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub levels: Option<Vec<Level>>,
+    /// Threshold used instead of the levels of the logger. Takes precedence over `levels` when set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub level: Option<LevelFilter>,
+    /// If styled placeholders write ANSI codes. Refer to [ColorMode]
+    #[serde(default)]
+    pub color: ColorMode,
+}
+
+impl TargetConfig {
+    /// The levels the target accepts. None if the target uses the levels of the logger
+    pub fn resolved_levels(&self) -> Option<Vec<Level>> {
+        match self.level {
+            Some(filter) => Some(levels_at_or_above(filter)),
+            None => self.levels.clone(),
+        }
+    }
+}
+
+fn optional_format_config<'de, D>(deserializer: D) -> Result<Option<FormatConfig>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    format_config_string_or_struct(deserializer).map(Some)
+}
+
+/// A target of a logger
+#[derive(Serialize, Clone)]
+#[serde(untagged)]
+pub enum LoggerTargetConfig {
+    /// The name of a target in [Config::targets]. Shared with every logger using the name
+    Named(String),
+    /// A target only used by this logger
+    Target(TargetConfig),
+}
+
+/// A string is the name of a target. A map is a [TargetConfig] so its errors are kept
+impl<'de> Deserialize<'de> for LoggerTargetConfig {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct NameOrTarget;
+
+        impl<'de> Visitor<'de> for NameOrTarget {
+            type Value = LoggerTargetConfig;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("target name or map")
+            }
+
+            fn visit_str<E>(self, value: &str) -> Result<LoggerTargetConfig, E>
+            where
+                E: serde::de::Error,
+            {
+                Ok(LoggerTargetConfig::Named(value.to_string()))
+            }
+
+            fn visit_map<M>(self, map: M) -> Result<LoggerTargetConfig, M::Error>
+            where
+                M: MapAccess<'de>,
+            {
+                Deserialize::deserialize(MapAccessDeserializer::new(map))
+                    .map(LoggerTargetConfig::Target)
+            }
+        }
+
+        deserializer.deserialize_any(NameOrTarget)
+    }
 }
 
 /// For Loggers with modules
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct LoggerConfig {
+    /// The module path. `my_app::*::db` and `my_app::**` are globs. `regex:` starts a regex.
+    /// Refer to [ModulePattern]
     pub module: Option<String>,
+    /// Match records by their target instead of the module path. `audit` or `audit*` to match by prefix.
+    /// Loggers with a target are checked before the module loggers
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
     /// Levels
     #[serde(default = "default_levels")]
     pub levels: Vec<Level>,
+    /// Threshold. `"info"` accepts info and everything more severe.
+    /// Takes precedence over `levels` when set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub level: Option<LevelFilter>,
     /// Targets
-    pub targets: Vec<TargetConfig>,
+    pub targets: Vec<LoggerTargetConfig>,
     /// Format
-    #[serde(deserialize_with = "format_config_string_or_struct")]
+    #[serde(default, deserialize_with = "format_config_string_or_struct")]
     pub format: FormatConfig,
+    /// Encoder. `text` uses the format. `json` writes JSON Lines
+    #[serde(default)]
+    pub encoder: Encoder,
     /// Structure Dump
     /// Dump the yaks
     #[serde(default)]
@@ -112,67 +217,418 @@ pub struct LoggerConfig {
     /// it will return true
     #[serde(default)]
     pub always_execute: bool,
+    /// `true` records are also sent to every parent logger. Including the root loggers.
+    /// A parent with `additive: false` gets the records but stops them going further up.
+    /// `false` records are never sent to the parent loggers. Even ones with `always_execute`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub additive: Option<bool>,
+    /// Use the targets and format of the closest parent logger. Only `level`/`levels` are taken from this logger.
+    /// Without a parent module logger the root loggers are used
+    #[serde(default)]
+    pub inherit_targets: bool,
 }
 
 fn default_levels() -> Vec<Level> {
     vec![Trace, Info, Debug, Warn, Error]
 }
 
-#[derive(Serialize, Deserialize)]
+impl LoggerConfig {
+    /// The levels the logger accepts. The `level` threshold if set otherwise `levels`
+    pub fn resolved_levels(&self) -> Vec<Level> {
+        match self.level {
+            Some(filter) => levels_at_or_above(filter),
+            None => self.levels.clone(),
+        }
+    }
+}
+
+/// Every level at or above the filter
+pub(crate) fn levels_at_or_above(filter: LevelFilter) -> Vec<Level> {
+    Level::iter().filter(|level| *level <= filter).collect()
+}
+
+#[derive(Serialize, Deserialize, Clone)]
 pub struct Config {
     /// All the logger
     #[serde(default)]
     pub loggers: Vec<LoggerConfig>,
     ///Default Loggers
     pub root_loggers: Vec<LoggerConfig>,
+    /// Targets built once and shared by every logger referencing them by name. Built in name order
+    #[serde(default)]
+    pub targets: BTreeMap<String, TargetConfig>,
+    /// Write records on a background thread. Refer to [AsyncConfig]
+    #[serde(default, rename = "async")]
+    pub asynchronous: Option<AsyncConfig>,
+    /// The overrides applied by [EnvOverrides::apply]. Applied again when the logger is reloaded
+    #[serde(skip)]
+    pub env_overrides: Option<EnvOverrides>,
 }
 
 pub fn create_loggers(
     config: Config,
-    builders: LoggerBuilders,
+    builders: &LoggerBuilders,
 ) -> Result<(Vec<Logger>, Vec<Logger>), crate::Error> {
-    Ok((
-        create_logger(config.root_loggers.into_iter(), &builders)?,
-        create_logger(config.loggers.into_iter(), &builders)?,
-    ))
+    let mut named = HashMap::new();
+    for (name, target) in config.targets {
+        let target = create_target(target, builders)
+            .map_err(|error| error.in_config(&format!("targets.{}", name)))?;
+        named.insert(name, target);
+    }
+    let mut root = Vec::new();
+    for (index, logger) in config.root_loggers.into_iter().enumerate() {
+        let path = format!("root_loggers[{}]", index);
+        if logger.target.is_some() {
+            return Err(crate::Error::RootLoggerTarget { path });
+        }
+        root.push(create_logger(logger, builders, &named, &path)?);
+    }
+    let loggers = create_module_loggers(config.loggers, builders, &named, &root)?;
+    Ok((root, loggers))
 }
 
 fn create_logger(
-    loggers: IntoIter<LoggerConfig>,
+    logger: LoggerConfig,
     builders: &LoggerBuilders,
+    named: &HashMap<String, LoggerOutput>,
+    path: &str,
+) -> Result<Logger, crate::Error> {
+    let module_pattern =
+        module_pattern(logger.module.as_deref()).map_err(|error| error.in_config(path))?;
+    let levels = logger.resolved_levels();
+    let mut targets = Vec::new();
+    for (index, target) in logger.targets.into_iter().enumerate() {
+        let target = match target {
+            LoggerTargetConfig::Named(name) => named
+                .get(&name)
+                .cloned()
+                .ok_or_else(|| undefined_target(name, named.keys())),
+            LoggerTargetConfig::Target(target) => create_target(target, builders),
+        };
+        targets.push(
+            target.map_err(|error| error.in_config(&format!("{}.targets[{}]", path, index)))?,
+        );
+    }
+    Ok(Logger {
+        module: logger.module,
+        target: logger.target,
+        module_pattern,
+        levels,
+        targets,
+        always_execute: logger.always_execute,
+        additive: logger.additive,
+        structure_dump: logger.structure_dump,
+        encoder: logger.encoder,
+        format: Arc::new(
+            Format::with_filters(
+                &builders.placeholders,
+                &builders.filters,
+                logger.format,
+                false,
+            )
+            .map_err(|error| error.in_config(&format!("{}.format", path)))?,
+        ),
+    })
+}
+
+/// Builds the module loggers. A logger with `inherit_targets` is replaced by a copy of each logger of its closest parent.
+/// The copies share the built targets and format of the parent.
+/// Parents are resolved first so inheriting from a logger that inherits works
+fn create_module_loggers(
+    loggers: Vec<LoggerConfig>,
+    builders: &LoggerBuilders,
+    named: &HashMap<String, LoggerOutput>,
+    root: &[Logger],
 ) -> Result<Vec<Logger>, crate::Error> {
-    let mut values = Vec::new();
-    for logger in loggers {
-        let mut targets = Vec::new();
-        for target in logger.targets {
-            targets.push(create_target(target, builders)?);
+    let mut order: Vec<usize> = (0..loggers.len()).collect();
+    order.sort_by_key(|index| module_depth(&loggers[*index]));
+    // The module of each logger. None for target loggers as they can not be parents
+    let modules: Vec<Option<String>> = loggers
+        .iter()
+        .map(|logger| logger.module.clone().filter(|_| logger.target.is_none()))
+        .collect();
+    let mut configs: Vec<Option<LoggerConfig>> = loggers.into_iter().map(Some).collect();
+    let mut resolved: Vec<Vec<Logger>> = (0..configs.len()).map(|_| Vec::new()).collect();
+    for index in order {
+        let logger = configs[index].take().expect("Each logger is resolved once");
+        let path = format!("loggers[{}]", index);
+        if logger.module.is_none() && logger.target.is_none() {
+            return Err(crate::Error::MissingModule { path });
+        }
+        if !logger.inherit_targets {
+            resolved[index] = vec![create_logger(logger, builders, named, &path)?];
+            continue;
+        }
+        let module_pattern =
+            module_pattern(logger.module.as_deref()).map_err(|error| error.in_config(&path))?;
+        let levels = logger.resolved_levels();
+        let parent = logger
+            .module
+            .as_deref()
+            .and_then(|module| closest_parent(module, &modules));
+        let inherited: Vec<&Logger> = match parent {
+            Some(parent) => modules
+                .iter()
+                .zip(&resolved)
+                .filter(|(module, _)| {
+                    module
+                        .as_deref()
+                        .is_some_and(|module| module.eq_ignore_ascii_case(parent))
+                })
+                .flat_map(|(_, loggers)| loggers.iter())
+                .collect(),
+            None => root.iter().collect(),
+        };
+        let mut copies: Vec<Logger> = inherited
+            .into_iter()
+            .map(|parent| Logger {
+                module: logger.module.clone(),
+                target: logger.target.clone(),
+                module_pattern: module_pattern.clone(),
+                levels: levels.clone(),
+                targets: parent.targets.clone(),
+                always_execute: logger.always_execute,
+                additive: logger.additive,
+                structure_dump: parent.structure_dump,
+                format: parent.format.clone(),
+                encoder: parent.encoder,
+            })
+            .collect();
+        if !logger.targets.is_empty() {
+            copies.insert(0, create_logger(logger, builders, named, &path)?);
         }
-        values.push(Logger {
-            module: logger.module,
-            levels: logger.levels,
-            targets,
-            always_execute: logger.always_execute,
-            structure_dump: logger.structure_dump,
-            format: Format::new(&builders.placeholders, logger.format, false)?,
-        });
+        resolved[index] = copies;
     }
-    Ok(values)
+    Ok(resolved.into_iter().flatten().collect())
+}
+/// The longest literal module that is a parent of the module. Compared without case like the LoggerTree
+fn closest_parent<'modules>(
+    module: &str,
+    modules: &'modules [Option<String>],
+) -> Option<&'modules str> {
+    modules
+        .iter()
+        .filter_map(|parent| parent.as_deref())
+        .filter(|parent| !ModulePattern::is_pattern(parent) && is_parent_module(parent, module))
+        .max_by_key(|parent| parent.len())
+}
+
+fn is_parent_module(parent: &str, module: &str) -> bool {
+    module
+        .get(..parent.len())
+        .is_some_and(|prefix| prefix.eq_ignore_ascii_case(parent))
+        && module[parent.len()..].starts_with("::")
+}
+
+fn module_depth(logger: &LoggerConfig) -> usize {
+    logger
+        .module
+        .as_deref()
+        .map(|module| module.split("::").count())
+        .unwrap_or(0)
+}
+
+/// Compiles the module if it is a glob or regex. Refer to [ModulePattern]
+fn module_pattern(module: Option<&str>) -> Result<Option<ModulePattern>, crate::Error> {
+    let Some(module) = module.filter(|module| ModulePattern::is_pattern(module)) else {
+        return Ok(None);
+    };
+    ModulePattern::new(module)
+        .map(Some)
+        .map_err(|error| crate::Error::InvalidModulePattern {
+            pattern: module.to_string(),
+            path: "module".to_string(),
+            message: error.to_string(),
+        })
 }
 
 fn create_target(
     target: TargetConfig,
     builders: &LoggerBuilders,
-) -> Result<Box<dyn LoggerTarget>, crate::Error> {
-    if let Some(target_builder) = builders
+) -> Result<LoggerOutput, crate::Error> {
+    let target_builder = find_target_builder(&target.target_type, builders)?;
+    let levels = target.resolved_levels();
+    let format = target
+        .format
+        .map(|format| {
+            Format::with_filters(&builders.placeholders, &builders.filters, format, false)
+        })
+        .transpose()
+        .map_err(|error| error.in_config("format"))?;
+    let built = target_builder
+        .build(target.properties, &builders.placeholders, &builders.filters)
+        .map_err(|error| target_error(&target.target_type, error))?;
+    Ok(LoggerOutput {
+        target: Arc::from(built),
+        format: format.map(Arc::new),
+        levels,
+        styles: target.color.forced(),
+    })
+}
+
+fn validate_target(target: &TargetConfig, builders: &LoggerBuilders) -> Result<(), crate::Error> {
+    let target_builder = find_target_builder(&target.target_type, builders)?;
+    if let Some(format) = &target.format {
+        Format::with_filters(
+            &builders.placeholders,
+            &builders.filters,
+            format.clone(),
+            false,
+        )
+        .map_err(|error| error.in_config("format"))?;
+    }
+    target_builder
+        .validate(
+            target.properties.clone(),
+            &builders.placeholders,
+            &builders.filters,
+        )
+        .map_err(|error| target_error(&target.target_type, error))
+}
+
+fn find_target_builder<'builders>(
+    target_type: &str,
+    builders: &'builders LoggerBuilders,
+) -> Result<&'builders dyn LoggerTargetBuilder, crate::Error> {
+    builders
         .targets
         .iter()
-        .find(|target_builder| target_builder.name().eq(&target.target_type))
-    {
-        match target_builder.build(target.properties, &builders.placeholders) {
-            Ok(value) => Ok(value),
-            Err(error) => Err(error),
-        }
+        .find(|target_builder| target_builder.name().eq(target_type))
+        .map(|target_builder| target_builder.as_ref())
+        .ok_or_else(|| crate::Error::UnknownTarget {
+            name: target_type.to_string(),
+            known: builders
+                .targets
+                .iter()
+                .map(|target_builder| target_builder.name().to_string())
+                .collect(),
+            path: "type".to_string(),
+        })
+}
+
+fn undefined_target<'names>(
+    name: String,
+    names: impl Iterator<Item = &'names String>,
+) -> crate::Error {
+    let mut known: Vec<String> = names.cloned().collect();
+    known.sort();
+    crate::Error::UndefinedTarget {
+        name,
+        known,
+        path: String::new(),
+    }
+}
+
+/// Points errors from the target builder at the properties of the target
+fn target_error(target_type: &str, error: crate::Error) -> crate::Error {
+    if error.has_config_path() {
+        error.in_config("properties")
     } else {
-        todo!("Implement Error Handler here")
+        crate::Error::InvalidTargetSettings {
+            target: target_type.to_string(),
+            path: "properties".to_string(),
+            message: error.to_string(),
+        }
+    }
+}
+
+impl Config {
+    pub fn from_json_str(config: &str) -> Result<Config, crate::Error> {
+        Ok(serde_json::from_str(config)?)
+    }
+    #[cfg(feature = "toml")]
+    pub fn from_toml_str(config: &str) -> Result<Config, crate::Error> {
+        Ok(toml::from_str(config)?)
+    }
+    #[cfg(feature = "yaml")]
+    pub fn from_yaml_str(config: &str) -> Result<Config, crate::Error> {
+        Ok(serde_yaml::from_str(config)?)
+    }
+    /// Reads the config file. The format is picked by the extension.
+    /// `json` is always supported. `toml` and `yaml`/`yml` require their features
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Config, crate::Error> {
+        let path = path.as_ref();
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(|extension| extension.to_ascii_lowercase());
+        match extension.as_deref() {
+            Some("json") => Self::from_json_str(&read_to_string(path)?),
+            #[cfg(feature = "toml")]
+            Some("toml") => Self::from_toml_str(&read_to_string(path)?),
+            #[cfg(feature = "yaml")]
+            Some("yaml" | "yml") => Self::from_yaml_str(&read_to_string(path)?),
+            _ => Err(crate::Error::UnsupportedConfigFile(
+                path.display().to_string(),
+            )),
+        }
+    }
+    /// Checks the config without installing a logger.
+    ///
+    /// Every Format and target is built with [LoggerTargetBuilder::validate] so no files are opened.
+    /// Returns every problem found instead of stopping at the first.
+    pub fn validate(&self, builders: &LoggerBuilders) -> Result<(), Vec<ValidationError>> {
+        let mut problems = Vec::new();
+        for (name, target) in &self.targets {
+            if let Err(error) = validate_target(target, builders) {
+                problems.push(ValidationError {
+                    logger: format!("targets.{}", name),
+                    module: None,
+                    error: error.in_config(&format!("targets.{}", name)),
+                });
+            }
+        }
+        let groups = [
+            ("root_loggers", &self.root_loggers, false),
+            ("loggers", &self.loggers, true),
+        ];
+        for (name, loggers, requires_module) in groups {
+            for (index, logger) in loggers.iter().enumerate() {
+                let path = format!("{}[{}]", name, index);
+                let mut report = |error: crate::Error| {
+                    problems.push(ValidationError {
+                        logger: path.clone(),
+                        module: logger.module.clone(),
+                        error,
+                    })
+                };
+                if requires_module && logger.module.is_none() && logger.target.is_none() {
+                    report(crate::Error::MissingModule { path: path.clone() });
+                }
+                if !requires_module && logger.target.is_some() {
+                    report(crate::Error::RootLoggerTarget { path: path.clone() });
+                }
+                if let Err(error) = module_pattern(logger.module.as_deref()) {
+                    report(error.in_config(&path));
+                }
+                for (index, target) in logger.targets.iter().enumerate() {
+                    let result = match target {
+                        LoggerTargetConfig::Named(name) if self.targets.contains_key(name) => {
+                            Ok(())
+                        }
+                        LoggerTargetConfig::Named(name) => {
+                            Err(undefined_target(name.clone(), self.targets.keys()))
+                        }
+                        LoggerTargetConfig::Target(target) => validate_target(target, builders),
+                    };
+                    if let Err(error) = result {
+                        report(error.in_config(&format!("{}.targets[{}]", path, index)));
+                    }
+                }
+                if let Err(error) = Format::with_filters(
+                    &builders.placeholders,
+                    &builders.filters,
+                    logger.format.clone(),
+                    false,
+                ) {
+                    report(error.in_config(&format!("{}.format", path)));
+                }
+            }
+        }
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(problems)
+        }
     }
 }
@@ -0,0 +1,121 @@
+//! Overrides for the log levels read from environment variables
+//!
+//! - `NITRO_LOG_LEVEL=warn` sets the level of the root loggers
+//! - `NITRO_LOG_MODULES=my_crate::db=trace,hyper=error` sets the level of module loggers.
+//!   A module without a logger gets a logger with `inherit_targets`. It shares the targets of the
+//!   closest parent logger or the root loggers.
+//!   A module without a level is set to `trace`. Modules are matched ignoring ASCII case like the logger tree
+use std::str::FromStr;
+
+use log::LevelFilter;
+
+use crate::config::{Config, FormatConfig, LoggerConfig};
+use crate::error::Error;
+use crate::loggers::encoder::Encoder;
+
+pub const LEVEL_VARIABLE: &str = "NITRO_LOG_LEVEL";
+pub const MODULES_VARIABLE: &str = "NITRO_LOG_MODULES";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EnvOverrides {
+    /// The level for the root loggers
+    pub level: Option<LevelFilter>,
+    /// The level for each module
+    pub modules: Vec<(String, LevelFilter)>,
+}
+
+impl EnvOverrides {
+    /// Reads the overrides from `NITRO_LOG_LEVEL` and `NITRO_LOG_MODULES`
+    pub fn from_env() -> Result<EnvOverrides, Error> {
+        Self::parse(
+            std::env::var(LEVEL_VARIABLE).ok().as_deref(),
+            std::env::var(MODULES_VARIABLE).ok().as_deref(),
+        )
+    }
+
+    /// Parses the values of the two variables
+    pub fn parse(level: Option<&str>, modules: Option<&str>) -> Result<EnvOverrides, Error> {
+        let level = level
+            .map(str::trim)
+            .filter(|level| !level.is_empty())
+            .map(|level| parse_level(LEVEL_VARIABLE, level))
+            .transpose()?;
+        let mut overrides = Vec::new();
+        for directive in modules.unwrap_or_default().split(',') {
+            let directive = directive.trim();
+            if directive.is_empty() {
+                continue;
+            }
+            let (module, level) = match directive.split_once('=') {
+                Some((module, level)) => {
+                    (module.trim(), parse_level(MODULES_VARIABLE, level.trim())?)
+                }
+                None => (directive, LevelFilter::Trace),
+            };
+            if module.is_empty() {
+                return Err(Error::InvalidEnvironment {
+                    variable: MODULES_VARIABLE.to_string(),
+                    message: format!("Missing module in `{}`", directive),
+                });
+            }
+            overrides.push((module.to_string(), level));
+        }
+        Ok(EnvOverrides {
+            level,
+            modules: overrides,
+        })
+    }
+
+    /// Applies the overrides to the config. The config keeps them for reloads
+    pub fn apply(&self, config: &mut Config) {
+        config.env_overrides = Some(self.clone());
+        if let Some(level) = self.level {
+            for logger in config.root_loggers.iter_mut() {
+                logger.level = Some(level);
+            }
+        }
+        for (module, level) in &self.modules {
+            let mut found = false;
+            for logger in config.loggers.iter_mut() {
+                let matches = logger
+                    .module
+                    .as_deref()
+                    .is_some_and(|logger_module| logger_module.eq_ignore_ascii_case(module));
+                if matches {
+                    logger.level = Some(*level);
+                    found = true;
+                }
+            }
+            if !found {
+                config.loggers.push(LoggerConfig {
+                    module: Some(module.clone()),
+                    target: None,
+                    levels: Vec::new(),
+                    level: Some(*level),
+                    targets: Vec::new(),
+                    format: FormatConfig::default(),
+                    encoder: Encoder::default(),
+                    structure_dump: false,
+                    always_execute: false,
+                    additive: None,
+                    inherit_targets: true,
+                });
+            }
+        }
+    }
+}
+
+impl Config {
+    /// Applies the overrides from `NITRO_LOG_LEVEL` and `NITRO_LOG_MODULES`. Refer to [EnvOverrides]
+    pub fn with_env_overrides(mut self) -> Result<Config, Error> {
+        EnvOverrides::from_env()?.apply(&mut self);
+        Ok(self)
+    }
+}
+
+fn parse_level(variable: &str, level: &str) -> Result<LevelFilter, Error> {
+    LevelFilter::from_str(level).map_err(|_| Error::InvalidEnvironment {
+        variable: variable.to_string(),
+        message: format!("Unknown level `{}`", level),
+    })
+}
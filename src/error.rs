@@ -1,3 +1,5 @@
+use std::fmt::{Display, Formatter};
+
 use log::SetLoggerError;
 
 use crate::format::FormatError;
@@ -11,10 +13,179 @@ pub enum Error {
     IOError(std::io::Error),
     #[error("{0}")]
     SerdeJson(serde_json::Error),
+    #[cfg(feature = "toml")]
+    #[error("{0}")]
+    Toml(toml::de::Error),
+    #[cfg(feature = "yaml")]
+    #[error("{0}")]
+    Yaml(serde_yaml::Error),
+    #[error("Unsupported config file `{0}`. Expected a json, toml or yaml extension with the matching feature enabled")]
+    UnsupportedConfigFile(String),
     #[error("Failed to generate a Format: {0}")]
     FormatGeneration(FormatError),
-    #[error("Failed to load {0} config Error {0}")]
+    #[error("Invalid format at `{path}` column {column}: {message}")]
+    InvalidFormat {
+        column: usize,
+        message: String,
+        path: String,
+    },
+    #[error("Failed to load {0} config Error {1}")]
     ConfigError(String, String),
+    #[error("Unknown target type `{name}` at `{path}`. Known targets: {}", known.join(", "))]
+    UnknownTarget {
+        name: String,
+        known: Vec<String>,
+        path: String,
+    },
+    #[error("Undefined target `{name}` at `{path}`. Defined targets: {}", known.join(", "))]
+    UndefinedTarget {
+        name: String,
+        known: Vec<String>,
+        path: String,
+    },
+    #[error("Invalid settings for target `{target}` at `{path}`: {message}")]
+    InvalidTargetSettings {
+        target: String,
+        path: String,
+        message: String,
+    },
+    #[error("Unknown placeholder `{name}` at `{path}`. Known placeholders: {}", known.join(", "))]
+    UnknownPlaceholder {
+        name: String,
+        known: Vec<String>,
+        path: String,
+    },
+    #[error("Invalid settings for placeholder `{placeholder}` at `{path}`: {message}")]
+    InvalidPlaceholderSettings {
+        placeholder: String,
+        path: String,
+        message: String,
+    },
+    #[error("Invalid module pattern `{pattern}` at `{path}`: {message}")]
+    InvalidModulePattern {
+        pattern: String,
+        path: String,
+        message: String,
+    },
+    #[error("Invalid value for {variable}: {message}")]
+    InvalidEnvironment { variable: String, message: String },
+    #[error(
+        "Logger at `{path}` is missing a module or target. Only root loggers can leave them out"
+    )]
+    MissingModule { path: String },
+    #[error("Root logger at `{path}` has a target. Move it to the loggers")]
+    RootLoggerTarget { path: String },
+}
+
+impl Error {
+    /// Prefixes the config path of errors that point into the config.
+    /// Used as the error travels up from the part of the config that failed
+    pub(crate) fn in_config(self, prefix: &str) -> Error {
+        match self {
+            Error::UnknownTarget { name, known, path } => Error::UnknownTarget {
+                name,
+                known,
+                path: join_path(prefix, &path),
+            },
+            Error::UndefinedTarget { name, known, path } => Error::UndefinedTarget {
+                name,
+                known,
+                path: join_path(prefix, &path),
+            },
+            Error::InvalidTargetSettings {
+                target,
+                path,
+                message,
+            } => Error::InvalidTargetSettings {
+                target,
+                path: join_path(prefix, &path),
+                message,
+            },
+            Error::UnknownPlaceholder { name, known, path } => Error::UnknownPlaceholder {
+                name,
+                known,
+                path: join_path(prefix, &path),
+            },
+            Error::InvalidPlaceholderSettings {
+                placeholder,
+                path,
+                message,
+            } => Error::InvalidPlaceholderSettings {
+                placeholder,
+                path: join_path(prefix, &path),
+                message,
+            },
+            Error::InvalidFormat {
+                column,
+                message,
+                path,
+            } => Error::InvalidFormat {
+                column,
+                message,
+                path: join_path(prefix, &path),
+            },
+            Error::MissingModule { path } => Error::MissingModule {
+                path: join_path(prefix, &path),
+            },
+            Error::RootLoggerTarget { path } => Error::RootLoggerTarget {
+                path: join_path(prefix, &path),
+            },
+            Error::InvalidModulePattern {
+                pattern,
+                path,
+                message,
+            } => Error::InvalidModulePattern {
+                pattern,
+                path: join_path(prefix, &path),
+                message,
+            },
+            error => error,
+        }
+    }
+
+    /// If the error already knows where in the config it happened
+    pub(crate) fn has_config_path(&self) -> bool {
+        matches!(
+            self,
+            Error::UnknownTarget { .. }
+                | Error::UndefinedTarget { .. }
+                | Error::InvalidTargetSettings { .. }
+                | Error::UnknownPlaceholder { .. }
+                | Error::InvalidPlaceholderSettings { .. }
+                | Error::MissingModule { .. }
+                | Error::InvalidFormat { .. }
+                | Error::InvalidModulePattern { .. }
+        )
+    }
+}
+
+/// A problem found by [crate::config::Config::validate]
+#[derive(Debug)]
+pub struct ValidationError {
+    /// The logger with the problem. `loggers[2]`
+    pub logger: String,
+    /// The module of the logger if it has one
+    pub module: Option<String>,
+    pub error: Error,
+}
+
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.module {
+            Some(module) => write!(f, "{} ({}): {}", self.logger, module, self.error),
+            None => write!(f, "{}: {}", self.logger, self.error),
+        }
+    }
+}
+
+fn join_path(prefix: &str, path: &str) -> String {
+    if path.is_empty() {
+        prefix.to_string()
+    } else if path.starts_with('[') {
+        format!("{}{}", prefix, path)
+    } else {
+        format!("{}.{}", prefix, path)
+    }
 }
 
 impl From<SetLoggerError> for Error {
@@ -29,6 +200,20 @@ impl From<serde_json::Error> for Error {
     }
 }
 
+#[cfg(feature = "toml")]
+impl From<toml::de::Error> for Error {
+    fn from(e: toml::de::Error) -> Self {
+        Error::Toml(e)
+    }
+}
+
+#[cfg(feature = "yaml")]
+impl From<serde_yaml::Error> for Error {
+    fn from(e: serde_yaml::Error) -> Self {
+        Error::Yaml(e)
+    }
+}
+
 impl From<std::io::Error> for Error {
     fn from(e: std::io::Error) -> Self {
         Error::IOError(e)
@@ -0,0 +1,78 @@
+use std::borrow::Cow;
+use std::fmt::Debug;
+
+use serde_json::Value;
+
+use crate::Error;
+
+pub mod standard_filters;
+
+pub type Filters = Vec<Box<dyn FilterBuilder>>;
+
+pub fn default_filters() -> Filters {
+    vec![
+        Box::new(standard_filters::UpperFilterBuilder {}),
+        Box::new(standard_filters::LowerFilterBuilder {}),
+        Box::new(standard_filters::TruncateFilterBuilder {}),
+        Box::new(standard_filters::PadFilterBuilder { left: true }),
+        Box::new(standard_filters::PadFilterBuilder { left: false }),
+        Box::new(standard_filters::JsonFilterBuilder {}),
+        Box::new(standard_filters::EscapeFilterBuilder {}),
+    ]
+}
+
+pub trait FilterBuilder: Send + Sync {
+    /// The name used after the `|`
+    fn name(&self) -> &'static str;
+    /// Create a new Filter from the arguments. `truncate(10)` has the arguments `[10]`
+    fn build(&self, args: Vec<Value>) -> Result<Box<dyn Filter>, Error>;
+}
+
+pub trait Filter: Send + Sync + Debug {
+    /// Transforms the rendered value
+    fn apply<'value>(&self, value: Cow<'value, str>) -> Cow<'value, str>;
+}
+
+/// Reads an optional argument. The error names the filter and argument
+pub fn parse_arg<D: serde::de::DeserializeOwned>(
+    filter: &str,
+    args: &[Value],
+    index: usize,
+    name: &str,
+) -> Result<Option<D>, Error> {
+    args.get(index)
+        .map(|value| serde_json::from_value(value.clone()))
+        .transpose()
+        .map_err(|error| {
+            Error::ConfigError(
+                "Filter".to_string(),
+                format!("Invalid `{}` for `{}`: {}", name, filter, error),
+            )
+        })
+}
+
+/// Reads an argument that must be set
+pub fn required_arg<D: serde::de::DeserializeOwned>(
+    filter: &str,
+    args: &[Value],
+    index: usize,
+    name: &str,
+) -> Result<D, Error> {
+    parse_arg(filter, args, index, name)?.ok_or_else(|| {
+        Error::ConfigError(
+            "Filter".to_string(),
+            format!("`{}` requires the argument `{}`", filter, name),
+        )
+    })
+}
+
+/// Errors if more arguments were passed than the filter takes
+pub fn max_args(filter: &str, args: &[Value], max: usize) -> Result<(), Error> {
+    if args.len() > max {
+        return Err(Error::ConfigError(
+            "Filter".to_string(),
+            format!("`{}` takes at most {} arguments", filter, max),
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,275 @@
+use std::borrow::Cow;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::filter::{max_args, parse_arg, required_arg, Filter, FilterBuilder};
+use crate::Error;
+
+pub struct UpperFilterBuilder;
+
+impl FilterBuilder for UpperFilterBuilder {
+    fn name(&self) -> &'static str {
+        "upper"
+    }
+
+    fn build(&self, args: Vec<Value>) -> Result<Box<dyn Filter>, Error> {
+        max_args(self.name(), &args, 0)?;
+        Ok(Box::new(UpperFilter))
+    }
+}
+
+#[derive(Debug)]
+pub struct UpperFilter;
+
+impl Filter for UpperFilter {
+    fn apply<'value>(&self, value: Cow<'value, str>) -> Cow<'value, str> {
+        Cow::Owned(map_text(&value, str::to_uppercase))
+    }
+}
+
+pub struct LowerFilterBuilder;
+
+impl FilterBuilder for LowerFilterBuilder {
+    fn name(&self) -> &'static str {
+        "lower"
+    }
+
+    fn build(&self, args: Vec<Value>) -> Result<Box<dyn Filter>, Error> {
+        max_args(self.name(), &args, 0)?;
+        Ok(Box::new(LowerFilter))
+    }
+}
+
+#[derive(Debug)]
+pub struct LowerFilter;
+
+impl Filter for LowerFilter {
+    fn apply<'value>(&self, value: Cow<'value, str>) -> Cow<'value, str> {
+        Cow::Owned(map_text(&value, str::to_lowercase))
+    }
+}
+
+pub struct TruncateFilterBuilder;
+
+impl FilterBuilder for TruncateFilterBuilder {
+    fn name(&self) -> &'static str {
+        "truncate"
+    }
+
+    fn build(&self, args: Vec<Value>) -> Result<Box<dyn Filter>, Error> {
+        max_args(self.name(), &args, 1)?;
+        Ok(Box::new(TruncateFilter(required_arg(
+            self.name(),
+            &args,
+            0,
+            "length",
+        )?)))
+    }
+}
+
+/// Keeps the first n characters. Style codes are kept so the style is still reset
+#[derive(Debug)]
+pub struct TruncateFilter(pub usize);
+
+impl Filter for TruncateFilter {
+    fn apply<'value>(&self, value: Cow<'value, str>) -> Cow<'value, str> {
+        let mut remaining = self.0;
+        let mut truncated = String::with_capacity(value.len());
+        let mut cut = false;
+        for (span, code) in spans(&value) {
+            if code {
+                truncated.push_str(span);
+                continue;
+            }
+            match span.char_indices().nth(remaining) {
+                Some((end, _)) => {
+                    truncated.push_str(&span[..end]);
+                    remaining = 0;
+                    cut = true;
+                }
+                None => {
+                    truncated.push_str(span);
+                    remaining -= span.chars().count();
+                }
+            }
+        }
+        if cut {
+            Cow::Owned(truncated)
+        } else {
+            value
+        }
+    }
+}
+
+/// `pad_left(n)` and `pad_right(n)`. An optional second argument sets the fill character
+pub struct PadFilterBuilder {
+    pub left: bool,
+}
+
+impl FilterBuilder for PadFilterBuilder {
+    fn name(&self) -> &'static str {
+        if self.left {
+            "pad_left"
+        } else {
+            "pad_right"
+        }
+    }
+
+    fn build(&self, args: Vec<Value>) -> Result<Box<dyn Filter>, Error> {
+        max_args(self.name(), &args, 2)?;
+        Ok(Box::new(PadFilter {
+            width: required_arg(self.name(), &args, 0, "width")?,
+            fill: parse_arg(self.name(), &args, 1, "fill")?.unwrap_or(' '),
+            // Padding the left side aligns the value to the right
+            align: if self.left { Align::Right } else { Align::Left },
+        }))
+    }
+}
+
+/// Where the value is placed within the width
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Align {
+    #[default]
+    Left,
+    Right,
+    Center,
+}
+
+/// Pads the value to at least `width` characters. ANSI escape codes are not counted
+#[derive(Debug)]
+pub struct PadFilter {
+    pub width: usize,
+    pub fill: char,
+    pub align: Align,
+}
+
+impl Filter for PadFilter {
+    fn apply<'value>(&self, value: Cow<'value, str>) -> Cow<'value, str> {
+        let length = visible_length(&value);
+        if length >= self.width {
+            return value;
+        }
+        let missing = self.width - length;
+        let (before, after) = match self.align {
+            Align::Left => (0, missing),
+            Align::Right => (missing, 0),
+            Align::Center => (missing / 2, missing - missing / 2),
+        };
+        let mut padded = String::with_capacity(value.len() + missing * self.fill.len_utf8());
+        for _ in 0..before {
+            padded.push(self.fill);
+        }
+        padded.push_str(&value);
+        for _ in 0..after {
+            padded.push(self.fill);
+        }
+        Cow::Owned(padded)
+    }
+}
+
+/// The number of characters without the `ESC [ ... m` style codes
+fn visible_length(value: &str) -> usize {
+    spans(value)
+        .filter(|(_, code)| !code)
+        .map(|(span, _)| span.chars().count())
+        .sum()
+}
+
+/// Maps the text between the style codes. The codes are kept as is
+fn map_text(value: &str, map: impl Fn(&str) -> String) -> String {
+    let mut mapped = String::with_capacity(value.len());
+    for (span, code) in spans(value) {
+        if code {
+            mapped.push_str(span);
+        } else {
+            mapped.push_str(&map(span));
+        }
+    }
+    mapped
+}
+
+/// Splits the value into text and `ESC [ ... m` style codes. True for a code
+fn spans(value: &str) -> impl Iterator<Item = (&str, bool)> {
+    let mut rest = value;
+    std::iter::from_fn(move || {
+        if rest.is_empty() {
+            return None;
+        }
+        let span = if rest.starts_with('\u{1b}') {
+            let end = rest
+                .char_indices()
+                .skip(1)
+                .find(|(_, next)| next.is_ascii_alphabetic())
+                .map(|(index, next)| index + next.len_utf8())
+                .unwrap_or(rest.len());
+            (&rest[..end], true)
+        } else {
+            let end = rest.find('\u{1b}').unwrap_or(rest.len());
+            (&rest[..end], false)
+        };
+        rest = &rest[span.0.len()..];
+        Some(span)
+    })
+}
+
+pub struct JsonFilterBuilder;
+
+impl FilterBuilder for JsonFilterBuilder {
+    fn name(&self) -> &'static str {
+        "json"
+    }
+
+    fn build(&self, args: Vec<Value>) -> Result<Box<dyn Filter>, Error> {
+        max_args(self.name(), &args, 0)?;
+        Ok(Box::new(JsonFilter))
+    }
+}
+
+/// Writes the value as a JSON string
+#[derive(Debug)]
+pub struct JsonFilter;
+
+impl Filter for JsonFilter {
+    fn apply<'value>(&self, value: Cow<'value, str>) -> Cow<'value, str> {
+        Cow::Owned(Value::String(value.into_owned()).to_string())
+    }
+}
+
+pub struct EscapeFilterBuilder;
+
+impl FilterBuilder for EscapeFilterBuilder {
+    fn name(&self) -> &'static str {
+        "escape"
+    }
+
+    fn build(&self, args: Vec<Value>) -> Result<Box<dyn Filter>, Error> {
+        max_args(self.name(), &args, 0)?;
+        Ok(Box::new(EscapeFilter))
+    }
+}
+
+/// Escapes control characters. So a value can not break the record over multiple lines
+#[derive(Debug)]
+pub struct EscapeFilter;
+
+impl Filter for EscapeFilter {
+    fn apply<'value>(&self, value: Cow<'value, str>) -> Cow<'value, str> {
+        if !value.chars().any(|c| c.is_control() || c == '\\') {
+            return value;
+        }
+        let mut escaped = String::with_capacity(value.len() + 8);
+        for c in value.chars() {
+            match c {
+                '\n' => escaped.push_str("\\n"),
+                '\r' => escaped.push_str("\\r"),
+                '\t' => escaped.push_str("\\t"),
+                '\\' => escaped.push_str("\\\\"),
+                c if c.is_control() => escaped.extend(c.escape_unicode()),
+                c => escaped.push(c),
+            }
+        }
+        Cow::Owned(escaped)
+    }
+}
@@ -0,0 +1,509 @@
+use std::borrow::Cow;
+use std::collections::VecDeque;
+use crate::config::FormatConfig;
+use crate::placeholder::PlaceholderBuilder;
+use crate::Placeholder;
+use log::Record;
+use serde::Deserialize;
+use serde_json::Value;
+use thiserror::Error;
+use crate::kv::Variable;
+
+use self::parser::{Alignment, Filter as FilterCall, Settings, Token};
+use crate::filter::standard_filters::{Align, PadFilter};
+use crate::filter::{default_filters, Filter, FilterBuilder};
+
+pub mod parser;
+
+#[derive(Debug, Error)]
+pub enum FormatError {
+    #[error("{0}")]
+    SettingParseError(serde_json::Error),
+    #[error("Missing Key {0}")]
+    MissingKey(String),
+}
+
+#[derive(Debug)]
+pub struct Format {
+    pub format: Vec<FormatSection>,
+}
+
+#[derive(Debug)]
+pub enum FormatSection {
+    Text(String),
+    Variable(Variable),
+    Placeholder(Box<dyn Placeholder>),
+    /// `{{name | default("anon")}}` the default is written if the variable is missing
+    Default {
+        variable: Variable,
+        default: String,
+    },
+    /// `{{#if name}}...{{/if}}` the sections are only written if the variable is set
+    Conditional {
+        variable: Variable,
+        sections: Vec<FormatSection>,
+    },
+    /// `{{name | upper}}` the filters are applied in order. A width is applied last
+    Filtered(Box<FormatSection>, Vec<Box<dyn Filter>>),
+    /// A section with a `style` setting
+    #[cfg(feature = "style-term")]
+    Styled(Box<FormatSection>, style_term::StylesContainer),
+}
+
+impl FormatSection {
+    /// Appends the section to the buffer. Avoids collecting nested sections into their own String
+    pub fn render_into(&self, record: &Record, buffer: &mut String) {
+        match self {
+            FormatSection::Text(value) => buffer.push_str(value),
+            FormatSection::Conditional { variable, sections } => {
+                if variable.find_value(record.key_values()).is_some() {
+                    for section in sections {
+                        section.render_into(record, buffer);
+                    }
+                }
+            }
+            section => buffer.push_str(&section.build_message(record)),
+        }
+    }
+    pub fn build_message<'message>(&'message self, record: &'message Record) -> Cow<'message, str> {
+        match self {
+            FormatSection::Text(value) => Cow::Borrowed(value),
+            FormatSection::Variable(variable) => {
+                Cow::Owned(variable.get_value(record.key_values()))
+            }
+            FormatSection::Placeholder(placeholder) => placeholder.build_message(record),
+            FormatSection::Default { variable, default } => variable
+                .find_value(record.key_values())
+                .map(Cow::Owned)
+                .unwrap_or(Cow::Borrowed(default)),
+            FormatSection::Conditional { variable, sections } => {
+                if variable.find_value(record.key_values()).is_none() {
+                    return Cow::Borrowed("");
+                }
+                Cow::Owned(
+                    sections
+                        .iter()
+                        .map(|section| section.build_message(record))
+                        .collect(),
+                )
+            }
+            FormatSection::Filtered(section, filters) => filters
+                .iter()
+                .fold(section.build_message(record), |value, filter| {
+                    filter.apply(value)
+                }),
+            #[cfg(feature = "style-term")]
+            FormatSection::Styled(section, styles) => {
+                let value = section.build_message(record);
+                if crate::placeholder::styles_enabled() {
+                    Cow::Owned(crate::placeholder::style_term::apply_styles(&value, styles))
+                } else {
+                    value
+                }
+            }
+        }
+    }
+}
+
+impl Format {
+    /// Appends every section of the record to the buffer
+    pub fn render_into(&self, record: &Record, buffer: &mut String) {
+        for section in &self.format {
+            section.render_into(record, buffer);
+        }
+    }
+    /// {{ placeholder({"format": "", "key": ""}) }}
+    /// {{ variable.name }}
+    ///
+    /// Refer to [parser] for the syntax.
+    ///
+    /// With the `style-term` feature any placeholder can be given a `style` setting.
+    /// `{{module({"style": {"text_color": "cyan"}})}}`. Variables use the last filter `{{user.id | style({...})}}`.
+    /// Text can be styled with the `text` placeholder `{{text({"value": " | ", "style": ...})}}`
+    ///
+    /// Any placeholder can be padded with `{"width": 5, "align": "right", "fill": " "}`.
+    /// Placeholders and variables can use the shorthand `{{level({}):>5}}`.
+    /// `{{level:>5}}` is an error as it would pad the variable `level` instead of the placeholder
+    /// Example format `Important Log Message Here  {{level({"color": true })}} {{ repository.name }}: {{message({})}}!!!`
+    pub fn new(
+        placeholders: &[Box<dyn PlaceholderBuilder>],
+        format: FormatConfig,
+        path_safe: bool,
+    ) -> Result<Format, crate::Error> {
+        Self::with_filters(placeholders, &default_filters(), format, path_safe)
+    }
+    /// [Format::new] with custom filters. Such as [LoggerBuilders::filters](crate::LoggerBuilders::filters)
+    pub fn with_filters(
+        placeholders: &[Box<dyn PlaceholderBuilder>],
+        filters: &[Box<dyn FilterBuilder>],
+        format: FormatConfig,
+        path_safe: bool,
+    ) -> Result<Format, crate::Error> {
+        let tokens = parser::parse(&format.format)
+            .map_err(|error| invalid_format(error.column, &error.message))?;
+        let mut sections = Vec::new();
+        // The open `{{#if}}` blocks with the sections before them
+        let mut open: Vec<(Variable, usize, Vec<FormatSection>)> = Vec::new();
+        for token in tokens {
+            match token {
+                Token::Text(value) => sections.push(FormatSection::Text(value)),
+                Token::Variable {
+                    name,
+                    alignment,
+                    filters: calls,
+                    column,
+                } => {
+                    if let Some(alignment) = alignment
+                        .filter(|_| placeholders.iter().any(|builder| builder.name() == name))
+                    {
+                        return Err(invalid_format(
+                            column,
+                            &format!(
+                                "`{}` is a placeholder. Use `{{{{{}({{}}):{}}}}}` to pad it",
+                                name,
+                                name,
+                                alignment.spec()
+                            ),
+                        ));
+                    }
+                    let pipeline = Pipeline {
+                        filters,
+                        calls,
+                        alignment,
+                        #[cfg(feature = "style-term")]
+                        path_safe,
+                    };
+                    sections.push(pipeline.apply(FormatSection::Variable(variable(&name)))?)
+                }
+                Token::Placeholder {
+                    name,
+                    settings,
+                    alignment,
+                    filters: calls,
+                    ..
+                } => sections.push(placeholder(
+                    placeholders,
+                    &format,
+                    &name,
+                    settings,
+                    Pipeline {
+                        filters,
+                        calls,
+                        alignment,
+                        #[cfg(feature = "style-term")]
+                        path_safe,
+                    },
+                    path_safe,
+                )?),
+                Token::If { name, column } => {
+                    open.push((variable(&name), column, std::mem::take(&mut sections)))
+                }
+                Token::EndIf { column } => {
+                    let Some((variable, _, before)) = open.pop() else {
+                        return Err(invalid_format(
+                            column,
+                            "`{{/if}}` without an open `{{#if}}`",
+                        ));
+                    };
+                    let inner = std::mem::replace(&mut sections, before);
+                    sections.push(FormatSection::Conditional {
+                        variable,
+                        sections: inner,
+                    });
+                }
+            }
+        }
+        if let Some((_, column, _)) = open.pop() {
+            return Err(invalid_format(
+                column,
+                "Unclosed `{{#if}}`. Expected `{{/if}}`",
+            ));
+        }
+        Ok(Format { format: sections })
+    }
+}
+
+fn placeholder(
+    placeholders: &[Box<dyn PlaceholderBuilder>],
+    format: &FormatConfig,
+    name: &str,
+    settings: Option<Settings>,
+    mut pipeline: Pipeline,
+    path_safe: bool,
+) -> Result<FormatSection, crate::Error> {
+    let invalid_settings = |path: &str, message: String| crate::Error::InvalidPlaceholderSettings {
+        placeholder: name.to_string(),
+        path: path.to_string(),
+        message,
+    };
+    let (mut settings, path) = match settings {
+        Some(settings) => {
+            let (placeholder_settings, path) = match settings {
+                Settings::Inline(settings) => (settings, String::new()),
+                Settings::Named(settings_name) => {
+                    let path = format!("placeholders.{}", settings_name);
+                    let settings = format
+                        .placeholders
+                        .get(&settings_name)
+                        .cloned()
+                        .ok_or_else(|| {
+                            invalid_settings(
+                                &path,
+                                format!("No placeholder settings named `{}`", settings_name),
+                            )
+                        })?;
+                    (settings, path)
+                }
+            };
+            if !placeholder_settings.is_object() {
+                return Err(invalid_settings(
+                    &path,
+                    "Settings must be a JSON object".to_string(),
+                ));
+            }
+            (Some(placeholder_settings), path)
+        }
+        None => (None, String::new()),
+    };
+    #[cfg(feature = "style-term")]
+    let style = match settings
+        .as_mut()
+        .and_then(|settings| settings.as_object_mut().and_then(|map| map.remove("style")))
+    {
+        Some(style) => Some(
+            crate::placeholder::style_term::parse_style(style)
+                .map_err(|message| invalid_settings(&join(&path, "style"), message))?,
+        ),
+        None => None,
+    };
+    let alignment = match settings.as_mut().and_then(Value::as_object_mut) {
+        Some(map) if map.contains_key("width") || map.contains_key("align") => {
+            let config = ["width", "align", "fill"]
+                .into_iter()
+                .filter_map(|key| map.remove(key).map(|value| (key.to_string(), value)))
+                .collect();
+            let config: AlignmentConfig = serde_json::from_value(Value::Object(config))
+                .map_err(|error| invalid_settings(&path, error.to_string()))?;
+            Some(Alignment {
+                fill: config.fill,
+                align: config.align,
+                width: config.width,
+            })
+        }
+        _ => None,
+    };
+    #[cfg(feature = "style-term")]
+    if style.is_some() && pipeline.calls.iter().any(|call| call.name == "style") {
+        return Err(invalid_settings(
+            &join(&path, "style"),
+            "Use either the `style` setting or the `style` filter".to_string(),
+        ));
+    }
+    // The shorthand wins over the settings
+    pipeline.alignment = pipeline.alignment.or(alignment);
+    let builder = placeholders.iter().find(|pb| pb.name().eq(name));
+    let section = match builder {
+        Some(builder) => {
+            if let Some(map) = settings.as_mut().and_then(Value::as_object_mut) {
+                map.insert("path".to_string(), Value::Bool(path_safe));
+            }
+            let placeholder = builder.build(settings).map_err(|error| {
+                let message = match error {
+                    crate::Error::ConfigError(_, message) => message,
+                    error => error.to_string(),
+                };
+                invalid_settings(&path, message)
+            })?;
+            FormatSection::Placeholder(placeholder)
+        }
+        None => {
+            return Err(crate::Error::UnknownPlaceholder {
+                name: name.to_string(),
+                known: placeholders
+                    .iter()
+                    .map(|pb| pb.name().to_string())
+                    .collect(),
+                path: String::new(),
+            })
+        }
+    };
+    let section = pipeline.apply(section)?;
+    #[cfg(feature = "style-term")]
+    let section = match style {
+        // Paths never get styles
+        Some(style) if !path_safe => FormatSection::Styled(Box::new(section), style),
+        _ => section,
+    };
+    Ok(section)
+}
+
+/// The `width` settings of a placeholder
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AlignmentConfig {
+    width: usize,
+    #[serde(default)]
+    align: Align,
+    #[serde(default = "default_fill")]
+    fill: char,
+}
+
+fn default_fill() -> char {
+    ' '
+}
+
+/// The filters, width and style of a section
+struct Pipeline<'filters> {
+    filters: &'filters [Box<dyn FilterBuilder>],
+    calls: Vec<FilterCall>,
+    alignment: Option<Alignment>,
+    /// Paths never get styles
+    #[cfg(feature = "style-term")]
+    path_safe: bool,
+}
+
+impl Pipeline<'_> {
+    fn apply(mut self, section: FormatSection) -> Result<FormatSection, crate::Error> {
+        #[cfg(feature = "style-term")]
+        let style = self.take_style()?;
+        let section = apply_filters(section, self.filters, std::mem::take(&mut self.calls))?;
+        let section = match self.alignment {
+            Some(alignment) => {
+                let pad: Box<dyn Filter> = Box::new(PadFilter {
+                    width: alignment.width,
+                    fill: alignment.fill,
+                    align: alignment.align,
+                });
+                match section {
+                    FormatSection::Filtered(section, mut filters) => {
+                        filters.push(pad);
+                        FormatSection::Filtered(section, filters)
+                    }
+                    section => FormatSection::Filtered(Box::new(section), vec![pad]),
+                }
+            }
+            None => section,
+        };
+        #[cfg(feature = "style-term")]
+        let section = match style {
+            Some(style) if !self.path_safe => FormatSection::Styled(Box::new(section), style),
+            _ => section,
+        };
+        Ok(section)
+    }
+
+    /// Removes the `style` filter. It wraps the section after the other filters and the width
+    #[cfg(feature = "style-term")]
+    fn take_style(&mut self) -> Result<Option<style_term::StylesContainer>, crate::Error> {
+        let Some(index) = self.calls.iter().position(|call| call.name == "style") else {
+            return Ok(None);
+        };
+        let call = self.calls.remove(index);
+        if index != self.calls.len() {
+            return Err(invalid_format(
+                call.column,
+                "`style` must be the last filter",
+            ));
+        }
+        let [style] = <[Value; 1]>::try_from(call.args)
+            .map_err(|_| invalid_format(call.column, "`style` takes one argument"))?;
+        crate::placeholder::style_term::parse_style(style)
+            .map(Some)
+            .map_err(|message| invalid_format(call.column, &message))
+    }
+}
+
+/// `default` turns a variable into [FormatSection::Default]. It has to be the first filter so the other filters apply to the value or the default.
+/// Every other filter is looked up in the filters
+fn apply_filters(
+    mut section: FormatSection,
+    filters: &[Box<dyn FilterBuilder>],
+    calls: Vec<FilterCall>,
+) -> Result<FormatSection, crate::Error> {
+    let mut applied = Vec::new();
+    for (index, call) in calls.into_iter().enumerate() {
+        if call.name == "default" {
+            if index != 0 {
+                return Err(invalid_format(
+                    call.column,
+                    "`default` must be the first filter",
+                ));
+            }
+            let FormatSection::Variable(variable) = section else {
+                return Err(invalid_format(
+                    call.column,
+                    "`default` only applies to variables",
+                ));
+            };
+            let [default] = <[Value; 1]>::try_from(call.args)
+                .map_err(|_| invalid_format(call.column, "`default` takes one argument"))?;
+            let default = match default {
+                Value::String(default) => default,
+                default => default.to_string(),
+            };
+            section = FormatSection::Default { variable, default };
+            continue;
+        }
+        let builder = filters
+            .iter()
+            .find(|builder| builder.name() == call.name)
+            .ok_or_else(|| {
+                let known: Vec<&str> = filters.iter().map(|builder| builder.name()).collect();
+                invalid_format(
+                    call.column,
+                    &format!(
+                        "Unknown filter `{}`. Known filters: default, {}{}",
+                        call.name,
+                        known.join(", "),
+                        if cfg!(feature = "style-term") {
+                            ", style"
+                        } else {
+                            ""
+                        }
+                    ),
+                )
+            })?;
+        let filter = builder.build(call.args).map_err(|error| {
+            let message = match error {
+                crate::Error::ConfigError(_, message) => message,
+                error => error.to_string(),
+            };
+            invalid_format(call.column, &message)
+        })?;
+        applied.push(filter);
+    }
+    if applied.is_empty() {
+        Ok(section)
+    } else {
+        Ok(FormatSection::Filtered(Box::new(section), applied))
+    }
+}
+
+fn invalid_format(column: usize, message: &str) -> crate::Error {
+    crate::Error::InvalidFormat {
+        column,
+        message: message.to_string(),
+        path: String::new(),
+    }
+}
+
+/// `{{name}}` or `{{name.path}}`
+fn variable(key: &str) -> Variable {
+    let key = key.trim();
+    if key.contains('.') {
+        let mut split: VecDeque<String> = key.split('.').map(|v| v.to_string()).collect();
+        let key = split.pop_front().unwrap();
+        Variable::PathVariable(key, split.into_iter().collect())
+    } else {
+        Variable::SinglePartVariable(key.to_string())
+    }
+}
+
+#[cfg(feature = "style-term")]
+fn join(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", path, key)
+    }
+}
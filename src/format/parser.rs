@@ -0,0 +1,430 @@
+//! The format language
+//!
+//! - `{{name}}` or `{{name.path}}` a key value variable
+//! - `{{name()}}`, `{{name({"key": "value"})}}` or `{{name(settings)}}` a placeholder.
+//!   Settings are inline JSON or the name of an entry in `placeholders`
+//! - `{{name | default("anon")}}` passes the value through filters. Arguments are JSON values. `default` goes first
+//! - `{{name:>5}}` pads the value to a width. `[[fill]align]width` where align is `<`, `>` or `^`.
+//!   Goes after the settings and before the filters `{{name({}):^9 | upper}}`
+//! - `{{#if name}}...{{/if}}` only writes the inner sections if the variable is set
+//! - `\{` and `\}` write a literal `{` and `}`. So `\{{` is a literal `{{`.
+//!   Other backslashes are kept as is so Windows paths work
+use serde_json::Value;
+
+use crate::filter::standard_filters::Align;
+
+/// A parsed part of the format
+#[derive(Debug, PartialEq)]
+pub enum Token {
+    Text(String),
+    Variable {
+        name: String,
+        alignment: Option<Alignment>,
+        filters: Vec<Filter>,
+        column: usize,
+    },
+    Placeholder {
+        name: String,
+        settings: Option<Settings>,
+        alignment: Option<Alignment>,
+        filters: Vec<Filter>,
+        column: usize,
+    },
+    /// `{{#if name}}`
+    If {
+        name: String,
+        column: usize,
+    },
+    /// `{{/if}}`
+    EndIf {
+        column: usize,
+    },
+}
+
+/// `| name` or `| name("argument", 1)`
+#[derive(Debug, PartialEq)]
+pub struct Filter {
+    pub name: String,
+    pub args: Vec<Value>,
+    pub column: usize,
+}
+
+/// `:>5` or `:*^9`
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Alignment {
+    pub fill: char,
+    pub align: Align,
+    pub width: usize,
+}
+
+impl Alignment {
+    /// The `[[fill]align]width` written after the `:`
+    pub fn spec(&self) -> String {
+        let align = match self.align {
+            Align::Left => '<',
+            Align::Right => '>',
+            Align::Center => '^',
+        };
+        match self.fill {
+            ' ' => format!("{}{}", align, self.width),
+            fill => format!("{}{}{}", fill, align, self.width),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Settings {
+    /// `{{name({"key": "value"})}}`
+    Inline(Value),
+    /// `{{name(settings)}}` refers to `placeholders.settings`
+    Named(String),
+}
+
+/// Where the format is invalid. The column counts characters starting at 1
+#[derive(Debug, PartialEq)]
+pub struct ParseError {
+    pub column: usize,
+    pub message: String,
+}
+
+pub fn parse(format: &str) -> Result<Vec<Token>, ParseError> {
+    Parser { format, pos: 0 }.parse()
+}
+
+struct Parser<'format> {
+    format: &'format str,
+    /// Byte offset into the format
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn parse(mut self) -> Result<Vec<Token>, ParseError> {
+        let mut tokens = Vec::new();
+        let mut text = String::new();
+        while let Some(next) = self.peek() {
+            if next == '\\' {
+                if let Some(escaped @ ('{' | '}')) = self.rest()[1..].chars().next() {
+                    text.push(escaped);
+                    self.pos += 2;
+                    continue;
+                }
+            }
+            if self.rest().starts_with("{{") {
+                if !text.is_empty() {
+                    tokens.push(Token::Text(std::mem::take(&mut text)));
+                }
+                tokens.push(self.parse_tag()?);
+                continue;
+            }
+            text.push(next);
+            self.pos += next.len_utf8();
+        }
+        if !text.is_empty() {
+            tokens.push(Token::Text(text));
+        }
+        Ok(tokens)
+    }
+
+    /// `{{` name [`(` settings `)`] [`:` alignment] [`|` filter]* `}}` or a block tag
+    fn parse_tag(&mut self) -> Result<Token, ParseError> {
+        let open = self.pos;
+        let column = self.column(open);
+        self.pos += 2;
+        self.skip_whitespace();
+        if self.eat("#") {
+            self.parse_keyword("if")?;
+            self.skip_whitespace();
+            let name = self.parse_name("Expected a variable name")?;
+            self.skip_whitespace();
+            self.expect("}}", open)?;
+            return Ok(Token::If { name, column });
+        }
+        if self.eat("/") {
+            self.parse_keyword("if")?;
+            self.skip_whitespace();
+            self.expect("}}", open)?;
+            return Ok(Token::EndIf { column });
+        }
+        let name = self.parse_name("Expected a placeholder or variable name")?;
+        self.skip_whitespace();
+        let settings = if self.eat("(") {
+            self.skip_whitespace();
+            let settings = match self.peek() {
+                None => return Err(self.unclosed(open, ")")),
+                Some(')') => None,
+                Some('{') => Some(Settings::Inline(self.parse_value("settings")?)),
+                _ => Some(Settings::Named(self.parse_named()?)),
+            };
+            self.skip_whitespace();
+            self.expect(")", open)?;
+            Some(settings)
+        } else {
+            None
+        };
+        self.skip_whitespace();
+        let alignment = if self.eat(":") {
+            Some(self.parse_alignment()?)
+        } else {
+            None
+        };
+        let filters = self.parse_filters(open)?;
+        self.expect("}}", open)?;
+        Ok(match settings {
+            Some(settings) => Token::Placeholder {
+                name,
+                settings,
+                alignment,
+                filters,
+                column,
+            },
+            None => Token::Variable {
+                name,
+                alignment,
+                filters,
+                column,
+            },
+        })
+    }
+
+    /// The keyword after `#` or `/`
+    fn parse_keyword(&mut self, keyword: &str) -> Result<(), ParseError> {
+        let start = self.pos;
+        let found = self.parse_name("Expected a block name")?;
+        if found != keyword {
+            return Err(self.error_at(start, &format!("Unknown block `{}`", found)));
+        }
+        Ok(())
+    }
+
+    fn parse_name(&mut self, message: &str) -> Result<String, ParseError> {
+        let start = self.pos;
+        while let Some(next) = self.peek() {
+            if next.is_whitespace() || matches!(next, '(' | ')' | '{' | '}' | '|' | ',' | ':') {
+                break;
+            }
+            self.pos += next.len_utf8();
+        }
+        if start == self.pos {
+            return Err(self.error_at(start, message));
+        }
+        Ok(self.format[start..self.pos].to_string())
+    }
+
+    /// `[[fill]align]width` after the `:`
+    fn parse_alignment(&mut self) -> Result<Alignment, ParseError> {
+        let start = self.pos;
+        while let Some(next) = self.peek() {
+            if next.is_whitespace() || matches!(next, '|' | '}') {
+                break;
+            }
+            self.pos += next.len_utf8();
+        }
+        let spec = &self.format[start..self.pos];
+        let align = |c: char| match c {
+            '<' => Some(Align::Left),
+            '>' => Some(Align::Right),
+            '^' => Some(Align::Center),
+            _ => None,
+        };
+        let mut chars = spec.chars();
+        let (fill, align, width) = match (chars.next(), chars.next().and_then(align)) {
+            (Some(fill), Some(second)) => (fill, second, chars.as_str()),
+            (Some(first), _) if align(first).is_some() => {
+                (' ', align(first).unwrap(), &spec[first.len_utf8()..])
+            }
+            _ => (' ', Align::Left, spec),
+        };
+        // `parse` would also accept a leading `+`
+        let width = Some(width)
+            .filter(|width| width.bytes().all(|byte| byte.is_ascii_digit()))
+            .and_then(|width| width.parse().ok());
+        match width {
+            Some(width) => Ok(Alignment { fill, align, width }),
+            _ => Err(self.error_at(
+                start,
+                &format!(
+                    "Invalid alignment `{}`. Expected `[[fill]<, > or ^]width`",
+                    spec
+                ),
+            )),
+        }
+    }
+
+    fn parse_filters(&mut self, open: usize) -> Result<Vec<Filter>, ParseError> {
+        let mut filters = Vec::new();
+        self.skip_whitespace();
+        while self.eat("|") {
+            self.skip_whitespace();
+            let column = self.column(self.pos);
+            let name = self.parse_name("Expected a filter name")?;
+            self.skip_whitespace();
+            let mut args = Vec::new();
+            if self.eat("(") {
+                self.skip_whitespace();
+                while !self.eat(")") {
+                    if self.peek().is_none() {
+                        return Err(self.unclosed(open, ")"));
+                    }
+                    args.push(self.parse_value("argument")?);
+                    self.skip_whitespace();
+                    if self.peek() != Some(')') {
+                        self.expect(",", open)?;
+                        self.skip_whitespace();
+                    }
+                }
+                self.skip_whitespace();
+            }
+            filters.push(Filter { name, args, column });
+        }
+        Ok(filters)
+    }
+
+    /// A JSON value. Strings and nested objects may contain any characters
+    fn parse_value(&mut self, kind: &str) -> Result<Value, ParseError> {
+        let start = self.pos;
+        match self.peek() {
+            Some('{' | '[') => self.skip_nested(kind)?,
+            Some('"') => self.skip_string()?,
+            _ => {
+                while let Some(next) = self.peek() {
+                    if next.is_whitespace() || matches!(next, ',' | ')' | '}') {
+                        break;
+                    }
+                    self.pos += next.len_utf8();
+                }
+            }
+        }
+        serde_json::from_str(&self.format[start..self.pos]).map_err(|error| {
+            let column = if error.line() == 1 {
+                self.column(start) + error.column().saturating_sub(1)
+            } else {
+                self.column(start)
+            };
+            // The position from serde_json is relative to the value
+            let message = error.to_string();
+            let position = format!(" at line {} column {}", error.line(), error.column());
+            ParseError {
+                column,
+                message: format!(
+                    "Invalid {}: {}",
+                    kind,
+                    message.strip_suffix(&position).unwrap_or(&message)
+                ),
+            }
+        })
+    }
+
+    /// Moves past a JSON object or array
+    fn skip_nested(&mut self, kind: &str) -> Result<(), ParseError> {
+        let start = self.pos;
+        let mut depth = 0usize;
+        loop {
+            let Some(byte) = self.format.as_bytes().get(self.pos) else {
+                return Err(self.error_at(start, &format!("Unclosed {} object", kind)));
+            };
+            match byte {
+                b'"' => {
+                    self.skip_string()?;
+                    continue;
+                }
+                b'{' | b'[' => depth += 1,
+                b'}' | b']' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        self.pos += 1;
+                        return Ok(());
+                    }
+                }
+                _ => {}
+            }
+            self.pos += 1;
+        }
+    }
+
+    /// Moves past a JSON string
+    fn skip_string(&mut self) -> Result<(), ParseError> {
+        let start = self.pos;
+        self.pos += 1;
+        loop {
+            match self.format.as_bytes().get(self.pos) {
+                None => return Err(self.error_at(start, "Unclosed string")),
+                Some(b'\\') => self.pos += 2,
+                Some(b'"') => {
+                    self.pos += 1;
+                    return Ok(());
+                }
+                Some(_) => self.pos += 1,
+            }
+        }
+    }
+
+    /// The name of an entry in `placeholders`
+    fn parse_named(&mut self) -> Result<String, ParseError> {
+        let start = self.pos;
+        while let Some(next) = self.peek() {
+            if next == ')' || next == '}' {
+                break;
+            }
+            self.pos += next.len_utf8();
+        }
+        let name = self.format[start..self.pos].trim();
+        if name.is_empty() {
+            return Err(self.error_at(start, "Expected settings"));
+        }
+        Ok(name.to_string())
+    }
+
+    fn expect(&mut self, expected: &str, open: usize) -> Result<(), ParseError> {
+        if self.eat(expected) {
+            return Ok(());
+        }
+        match self.peek() {
+            None => Err(self.unclosed(open, expected)),
+            Some(found) => Err(self.error_at(
+                self.pos,
+                &format!("Expected `{}` found `{}`", expected, found),
+            )),
+        }
+    }
+
+    fn unclosed(&self, open: usize, expected: &str) -> ParseError {
+        ParseError {
+            column: self.column(open),
+            message: format!("Unclosed `{{{{`. Expected `{}`", expected),
+        }
+    }
+
+    fn eat(&mut self, expected: &str) -> bool {
+        if self.rest().starts_with(expected) {
+            self.pos += expected.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(next) = self.peek().filter(|next| next.is_whitespace()) {
+            self.pos += next.len_utf8();
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn rest(&self) -> &str {
+        &self.format[self.pos..]
+    }
+
+    fn column(&self, pos: usize) -> usize {
+        self.format[..pos].chars().count() + 1
+    }
+
+    fn error_at(&self, pos: usize, message: &str) -> ParseError {
+        ParseError {
+            column: self.column(pos),
+            message: message.to_string(),
+        }
+    }
+}
@@ -1,7 +1,13 @@
 use log::kv::{Source, ToKey};
+use serde_json::Value;
 
 pub mod default_structure_dump;
+pub mod owned;
 
+/// A key value used in a format. `{{user}}` or a path into a structured value `{{user.id}}`.
+///
+/// A path renders strings as is and any other JSON value as JSON. `{{user.id}}` renders `7` and `{{user.roles}}` renders `["admin"]`.
+/// A missing key or a null renders `{undefined}`
 #[derive(Debug, Clone)]
 pub enum Variable {
     PathVariable(String, Vec<String>),
@@ -11,33 +17,30 @@ pub enum Variable {
 impl Variable {
     /// Locates the Value
     pub(crate) fn get_value<'kvs>(&'kvs self, source: &'kvs dyn Source) -> String {
+        self.find_value(source).unwrap_or_else(undefined)
+    }
+    /// Locates the Value. None if the key or path is missing
+    pub(crate) fn find_value(&self, source: &dyn Source) -> Option<String> {
         match self {
             Variable::PathVariable(key, path) => {
-                let option = source.get(key.to_key());
-                if let Some(value) = option {
-                    let value = serde_json::to_value(value);
-                    match value.as_ref() {
-                        Ok(ok) => {
-                            let mut value = ok;
-                            for inner_key in path {
-                                if let Some(inner_key) = value.get(inner_key) {
-                                    value = inner_key
-                                } else {
-                                    return undefined();
-                                }
-                            }
-                            value.as_str().map(|v| v.to_owned()).unwrap_or_else(undefined)
+                let value = source.get(key.to_key())?;
+                match serde_json::to_value(value) {
+                    Ok(ok) => {
+                        let mut value = &ok;
+                        for inner_key in path {
+                            value = value.get(inner_key)?;
                         }
-                        Err(error) => {
-                            format!("(Unable to parse via serde_json: {})", error)
+                        match value {
+                            Value::Null => None,
+                            Value::String(value) => Some(value.clone()),
+                            value => Some(value.to_string()),
                         }
                     }
-                } else {
-                    undefined()
+                    Err(error) => Some(format!("(Unable to parse via serde_json: {})", error)),
                 }
             }
             Variable::SinglePartVariable(variable) => {
-                source.get(variable.to_key()).map(|v| v.to_string()).unwrap_or_else(undefined)
+                source.get(variable.to_key()).map(|v| v.to_string())
             }
         }
     }
@@ -45,4 +48,4 @@ impl Variable {
 
 fn undefined() -> String {
     "{undefined}".to_owned()
-}
\ No newline at end of file
+}
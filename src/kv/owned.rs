@@ -0,0 +1,47 @@
+use log::kv::{Error, Key, Source, ToValue, Value, Visitor};
+
+/// Key-values copied out of a record so it can outlive the `log!` call
+#[derive(Debug, Clone, Default)]
+pub struct OwnedKeyValues(pub Vec<(String, OwnedValue)>);
+
+#[derive(Debug, Clone)]
+pub enum OwnedValue {
+    Text(String),
+    Json(serde_json::Value),
+}
+
+impl OwnedKeyValues {
+    pub fn capture(source: &dyn Source) -> OwnedKeyValues {
+        let mut values = OwnedKeyValues::default();
+        // Visiting only fails if the visitor does
+        let _ = source.visit(&mut values);
+        values
+    }
+}
+
+impl<'kvs> Visitor<'kvs> for OwnedKeyValues {
+    fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), Error> {
+        let value = match serde_json::to_value(&value) {
+            Ok(serde_json::Value::String(text)) => OwnedValue::Text(text),
+            Ok(json) => OwnedValue::Json(json),
+            Err(_) => OwnedValue::Text(value.to_string()),
+        };
+        self.0.push((key.as_str().to_owned(), value));
+        Ok(())
+    }
+}
+
+impl ToValue for OwnedValue {
+    fn to_value(&self) -> Value<'_> {
+        match self {
+            OwnedValue::Text(text) => Value::from(text.as_str()),
+            OwnedValue::Json(json) => Value::from_serde(json),
+        }
+    }
+}
+
+impl Source for OwnedKeyValues {
+    fn visit<'kvs>(&'kvs self, visitor: &mut dyn Visitor<'kvs>) -> Result<(), Error> {
+        self.0.visit(visitor)
+    }
+}
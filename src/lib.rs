@@ -1,20 +1,35 @@
 
-use log::{LevelFilter, Metadata, Record};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+use std::thread;
+use std::time::Duration;
 
+use log::{Metadata, Record};
+
+use crate::asynchronous::{AsyncQueue, CloseOnExit, OwnedRecord};
 use crate::config::Config;
+use crate::env::EnvOverrides;
 use crate::error::Error;
+use crate::filter::{default_filters, Filters};
 use crate::loggers::target::{default_logger_targets, LoggerTargetBuilders};
 use crate::loggers::tree::LoggerTree;
 use crate::loggers::Logger;
 
 use crate::placeholder::{default_placeholders, PlaceHolders, Placeholder};
+use crate::reload::ConfigWatcher;
 
+pub mod asynchronous;
 pub mod config;
+pub mod env;
 pub mod error;
+pub mod filter;
 pub mod format;
 pub mod kv;
 pub mod loggers;
 pub mod placeholder;
+pub mod reload;
+pub(crate) mod time;
 
 pub type ErrorHandler = Box<dyn Send + Sync + Fn(&anyhow::Error)>;
 
@@ -25,6 +40,8 @@ fn default_error_handler(error: &anyhow::Error) {
 pub struct LoggerBuilders {
     pub placeholders: PlaceHolders,
     pub targets: LoggerTargetBuilders,
+    /// Filters used after `|` in formats
+    pub filters: Filters,
 }
 
 impl Default for LoggerBuilders {
@@ -32,75 +49,196 @@ impl Default for LoggerBuilders {
         LoggerBuilders {
             placeholders: default_placeholders(),
             targets: default_logger_targets(),
+            filters: default_filters(),
         }
     }
 }
 
 pub struct NitroLogger {
-    loggers: LoggerTree,
+    /// Swapped out when the config is reloaded
+    loggers: RwLock<Arc<LoggerTree>>,
     pub(crate) error_handler: Box<dyn Send + Sync + Fn(&anyhow::Error)>,
+    /// Set if the records are written on the background thread
+    queue: Option<AsyncQueue>,
+}
+
+/// Returned when the logger is loaded. Allows interacting with the installed logger
+#[derive(Clone)]
+pub struct LoggerHandle {
+    logger: &'static NitroLogger,
+    builders: Arc<LoggerBuilders>,
+    /// The environment overrides of the loaded config
+    env_overrides: Option<EnvOverrides>,
+}
+
+impl LoggerHandle {
+    /// Builds the loggers from the new config and swaps them in.
+    /// If the config fails to build the current loggers are kept.
+    ///
+    /// The async settings can not be changed after loading and are ignored.
+    /// The environment overrides of the loaded config are applied unless the new config has its own
+    pub fn reload(&self, mut config: Config) -> Result<(), Error> {
+        if let (None, Some(overrides)) = (&config.env_overrides, &self.env_overrides) {
+            overrides.apply(&mut config);
+        }
+        let (root, loggers) = config::create_loggers(config, &self.builders)?;
+        self.logger.set_loggers(LoggerTree::new(root, loggers));
+        Ok(())
+    }
+    /// Starts a thread that reloads the config whenever the file's modified time changes.
+    /// The file is read with [Config::from_path]. The environment overrides of the loaded config are kept.
+    /// Reload errors are passed to the error handler.
+    ///
+    /// The watcher stops once the returned ConfigWatcher is dropped
+    pub fn watch(
+        &self,
+        path: impl Into<PathBuf>,
+        interval: Duration,
+    ) -> Result<ConfigWatcher, Error> {
+        ConfigWatcher::start(self.clone(), path.into(), interval)
+    }
+
+    /// The number of records dropped because the async queue was full. Always 0 if not async
+    pub fn dropped_records(&self) -> u64 {
+        self.logger
+            .queue
+            .as_ref()
+            .map(|queue| queue.dropped())
+            .unwrap_or(0)
+    }
 }
 
 impl NitroLogger {
     /// Load the Config via the Config the object
-    pub fn load(config: Config, builders: LoggerBuilders) -> Result<(), Error> {
-        let (root, loggers) = config::create_loggers(config, builders)?;
-        let result = Self::new(
-            LoggerTree::new(root, loggers),
-            Box::new(default_error_handler),
-        );
-        log::set_boxed_logger(Box::new(result))?;
-        log::set_max_level(LevelFilter::Trace);
-        Ok(())
+    pub fn load(config: Config, builders: LoggerBuilders) -> Result<LoggerHandle, Error> {
+        Self::load_with_error_handler(config, builders, Box::new(default_error_handler))
     }
     pub fn load_with_error_handler(
-        config: Config,
+        mut config: Config,
         builders: LoggerBuilders,
         error_handler: ErrorHandler,
-    ) -> Result<(), Error> {
-        let (root, loggers) = config::create_loggers(config, builders)?;
-        let result = Self::new(LoggerTree::new(root, loggers), error_handler);
-        log::set_boxed_logger(Box::new(result))?;
-        log::set_max_level(LevelFilter::Trace);
-        Ok(())
+    ) -> Result<LoggerHandle, Error> {
+        let asynchronous = config.asynchronous.take();
+        let env_overrides = config.env_overrides.clone();
+        let (root, loggers) = config::create_loggers(config, &builders)?;
+        let mut result = Self::new(LoggerTree::new(root, loggers), error_handler);
+        result.queue = asynchronous.map(AsyncQueue::from);
+        result.install(builders, env_overrides)
     }
     /// Create a new Nitro Logger with the already setup LoggerTree
     pub fn new(loggers: LoggerTree, error_handler: ErrorHandler) -> NitroLogger {
         NitroLogger {
-            loggers,
+            loggers: RwLock::new(Arc::new(loggers)),
             error_handler,
+            queue: None,
+        }
+    }
+    /// Starts the writer thread if async. Then sets the global logger
+    ///
+    /// The logger is only installed once the writer thread is running. So records never wait on a missing thread
+    fn install(
+        self,
+        builders: LoggerBuilders,
+        env_overrides: Option<EnvOverrides>,
+    ) -> Result<LoggerHandle, Error> {
+        // The global logger lives for the rest of the program. The writer thread and handle borrow it
+        let logger: &'static NitroLogger = Box::leak(Box::new(self));
+        if let Some(queue) = &logger.queue {
+            thread::Builder::new()
+                .name("nitro_log".to_string())
+                .spawn(move || {
+                    let _close = CloseOnExit(queue);
+                    asynchronous::set_writer_thread();
+                    while let Some(record) = queue.pop() {
+                        let written = panic::catch_unwind(AssertUnwindSafe(|| {
+                            record.with_record(|record| logger.dispatch(record))
+                        }));
+                        if written.is_err() {
+                            (logger.error_handler)(&anyhow::anyhow!(
+                                "A target panicked while writing a record. The record was dropped"
+                            ));
+                        }
+                        queue.finish();
+                    }
+                })?;
+        }
+        if let Err(error) = log::set_logger(logger) {
+            // Stops the writer thread. Nothing can log to it
+            if let Some(queue) = &logger.queue {
+                queue.close();
+            }
+            return Err(error.into());
         }
+        log::set_max_level(logger.loggers().max_level());
+        Ok(LoggerHandle {
+            logger,
+            builders: Arc::new(builders),
+            env_overrides,
+        })
+    }
+    /// The current LoggerTree
+    fn loggers(&self) -> Arc<LoggerTree> {
+        self.loggers
+            .read()
+            .unwrap_or_else(|error| error.into_inner())
+            .clone()
+    }
+    /// Replaces the LoggerTree. Records being written finish with the old tree
+    fn set_loggers(&self, loggers: LoggerTree) {
+        log::set_max_level(loggers.max_level());
+        let old = std::mem::replace(
+            &mut *self
+                .loggers
+                .write()
+                .unwrap_or_else(|error| error.into_inner()),
+            Arc::new(loggers),
+        );
+        for logger in old.all_loggers() {
+            logger.flush(self);
+        }
+    }
+    /// Formats and writes the record to every matching logger
+    fn dispatch(&self, record: &Record) {
+        let tree = self.loggers();
+        for logger in tree.route(record.target(), record.module_path()) {
+            if logger.accepts(record.level()) {
+                logger.log(record, self);
+            }
+        }
+    }
+    /// Checks if any logger would write the record. So unused records are not queued
+    fn accepts(&self, record: &Record) -> bool {
+        self.loggers()
+            .accepts(record.target(), record.module_path(), record.level())
     }
 }
 
 impl log::Log for NitroLogger {
+    /// Uses the same lookup as `log`. Metadata does not have the module path.
+    /// So the target is used in its place. Which is what `log_enabled!` does by default
     fn enabled(&self, metadata: &Metadata) -> bool {
-        let option = self.loggers.find_logger(metadata.target());
-        if option.is_none() {
-            return false;
-        }
-        let loggers = option.unwrap();
-        for logger in loggers {
-            if logger.levels.contains(&metadata.level()) {
-                return true;
-            }
-        }
-        true
+        self.loggers()
+            .accepts(metadata.target(), Some(metadata.target()), metadata.level())
     }
 
     fn log(&self, record: &Record) {
-        let option = self.loggers.find_logger(record.module_path().unwrap());
-        if option.is_none() {
-            panic!("No Loggers Found!");
-        }
-
-        let loggers = option.unwrap();
-        for logger in loggers {
-            if logger.levels.contains(&record.metadata().level()) {
-                logger.log(record, self);
+        match &self.queue {
+            Some(queue) => {
+                if self.accepts(record) {
+                    queue.push(OwnedRecord::from(record));
+                }
             }
+            None => self.dispatch(record),
         }
     }
 
-    fn flush(&self) {}
+    /// Waits for the async queue to drain. Then flushes every target
+    fn flush(&self) {
+        if let Some(queue) = &self.queue {
+            queue.wait_until_drained();
+        }
+        for logger in self.loggers().all_loggers() {
+            logger.flush(self);
+        }
+    }
 }
@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+
+/// Set to anything but an empty string to turn off colors. <https://no-color.org>
+pub const NO_COLOR: &str = "NO_COLOR";
+/// Set to anything but `0` to turn on colors even if the target is not a terminal
+pub const CLICOLOR_FORCE: &str = "CLICOLOR_FORCE";
+
+/// If styled placeholders write ANSI codes to a target
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorMode {
+    /// Checks `NO_COLOR` and `CLICOLOR_FORCE`. Then if the target is a terminal
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorMode {
+    /// Resolves the mode with the environment variables.
+    /// None if it is up to the target
+    pub fn forced(self) -> Option<bool> {
+        match self {
+            ColorMode::Always => Some(true),
+            ColorMode::Never => Some(false),
+            ColorMode::Auto => {
+                let set = |variable: &str| std::env::var_os(variable).filter(|v| !v.is_empty());
+                if set(NO_COLOR).is_some() {
+                    Some(false)
+                } else if set(CLICOLOR_FORCE).is_some_and(|value| value != "0") {
+                    Some(true)
+                } else {
+                    None
+                }
+            }
+        }
+    }
+}
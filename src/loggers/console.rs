@@ -1,7 +1,9 @@
-use log::Record;
+use log::{Level, Record};
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::io::{stdout, Stdout};
+use std::io::{stderr, stdout, IsTerminal, Stderr, Stdout, Write};
 
+use crate::filter::Filters;
 use crate::loggers::target::LoggerTargetBuilder;
 use crate::loggers::{LoggerTarget, LoggerWriter};
 use crate::{Error, PlaceHolders};
@@ -16,26 +18,89 @@ impl LoggerTargetBuilder for ConsoleLoggerBuilder {
 
     fn build(
         &self,
-        _value: Value,
+        value: Value,
         _placeholders: &PlaceHolders,
+        _filters: &Filters,
     ) -> Result<Box<dyn LoggerTarget>, Error> {
-        let logger = ConsoleLogger { console: stdout() };
+        let config: ConsoleConfig = if value.is_null() {
+            ConsoleConfig::default()
+        } else {
+            serde_json::from_value(value)?
+        };
+        let logger = ConsoleLogger {
+            stream: config.stream,
+            stdout: stdout(),
+            stderr: stderr(),
+            stdout_terminal: stdout().is_terminal(),
+            stderr_terminal: stderr().is_terminal(),
+        };
         Ok(Box::new(logger))
     }
 }
 
+#[derive(Serialize, Deserialize, Default)]
+pub struct ConsoleConfig {
+    /// Where the records are written. Refer to [ConsoleStream]
+    #[serde(default)]
+    pub stream: ConsoleStream,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ConsoleStream {
+    #[default]
+    Stdout,
+    Stderr,
+    /// Warn and Error go to stderr. Everything else to stdout
+    Split,
+}
+
+impl ConsoleStream {
+    /// If the level is written to stderr
+    pub fn is_stderr(&self, level: Level) -> bool {
+        match self {
+            ConsoleStream::Stdout => false,
+            ConsoleStream::Stderr => true,
+            ConsoleStream::Split => level <= Level::Warn,
+        }
+    }
+}
+
 pub struct ConsoleLogger {
-    pub console: Stdout,
+    pub stream: ConsoleStream,
+    pub stdout: Stdout,
+    pub stderr: Stderr,
+    /// Checked once when the target is built
+    pub stdout_terminal: bool,
+    pub stderr_terminal: bool,
 }
 
 impl LoggerTarget for ConsoleLogger {
     fn start_write<'log>(&'log self, record: &'log Record) -> anyhow::Result<LoggerWriter<'log>> {
-        let _x = Box::new(self.console.lock());
+        let internal: Box<dyn Write> = if self.stream.is_stderr(record.level()) {
+            Box::new(self.stderr.lock())
+        } else {
+            Box::new(self.stdout.lock())
+        };
         Ok(LoggerWriter {
-            internal: Box::new(self.console.lock()),
+            internal,
             record,
             logger: Box::new(self),
         })
     }
 
+    /// Styles are only written to a terminal
+    fn supports_styles(&self, record: &Record) -> bool {
+        if self.stream.is_stderr(record.level()) {
+            self.stderr_terminal
+        } else {
+            self.stdout_terminal
+        }
+    }
+
+    fn flush(&self) -> anyhow::Result<()> {
+        self.stdout.lock().flush()?;
+        self.stderr.lock().flush()?;
+        Ok(())
+    }
 }
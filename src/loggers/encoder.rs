@@ -0,0 +1,52 @@
+use log::kv::{Error, Key, Value, Visitor};
+use log::Record;
+use serde::{Deserialize, Serialize};
+use serde_json::Map;
+
+use crate::placeholder::record_time;
+use crate::time::rfc3339;
+
+/// How a Logger turns a record into a line
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Encoder {
+    /// The format of the logger. Followed by the structure dump if enabled
+    #[default]
+    Text,
+    /// One JSON object per line. The format and structure dump are ignored
+    Json,
+}
+
+/// Creates the JSON object for the record without the trailing new line.
+///
+/// Every key-value is added at the top level of the object.
+/// If a key-value uses the name of one of the standard fields the standard field wins.
+pub fn json_line(record: &Record) -> String {
+    let mut values = JsonKeyValues(Map::new());
+    if let Err(error) = record.key_values().visit(&mut values) {
+        values.0.insert(
+            "key_values_error".to_string(),
+            serde_json::Value::String(error.to_string()),
+        );
+    }
+    let mut object = values.0;
+    object.insert("timestamp".to_string(), rfc3339(record_time()).into());
+    object.insert("level".to_string(), record.level().as_str().into());
+    object.insert("target".to_string(), record.target().into());
+    object.insert("module_path".to_string(), record.module_path().into());
+    object.insert("file".to_string(), record.file().into());
+    object.insert("line".to_string(), record.line().into());
+    object.insert("message".to_string(), record.args().to_string().into());
+    serde_json::Value::Object(object).to_string()
+}
+
+struct JsonKeyValues(Map<String, serde_json::Value>);
+
+impl<'kvs> Visitor<'kvs> for JsonKeyValues {
+    fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), Error> {
+        let value = serde_json::to_value(&value)
+            .unwrap_or_else(|_| serde_json::Value::String(value.to_string()));
+        self.0.insert(key.as_str().to_string(), value);
+        Ok(())
+    }
+}
@@ -0,0 +1,185 @@
+use std::collections::HashMap;
+use std::fs::{create_dir_all, metadata, File, Metadata, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::loggers::file::rolling::Roller;
+
+/// Open files of a file_logger target keyed by the rendered path.
+///
+/// Holds at most `max_open` files. When full the least recently used file is closed.
+/// Files that have not been written to for `idle_timeout` are closed on the next write to the target.
+///
+/// Each path has its own lock. Checking, rolling and opening a file only blocks writers of the same path
+pub struct FileHandles {
+    pub max_open: usize,
+    pub idle_timeout: Option<Duration>,
+    open: Mutex<HashMap<PathBuf, CachedPath>>,
+}
+
+struct CachedPath {
+    file: Arc<Mutex<Option<OpenFile>>>,
+    last_used: Instant,
+}
+
+struct OpenFile {
+    file: Arc<File>,
+    identity: FileIdentity,
+}
+
+impl FileHandles {
+    pub fn new(max_open: usize, idle_timeout: Option<Duration>) -> FileHandles {
+        FileHandles {
+            max_open: max_open.max(1),
+            idle_timeout,
+            open: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns the open file for the path. Opening it if it is not cached.
+    ///
+    /// The file is reopened if it was deleted or replaced since it was opened.
+    /// If a roller is provided the file is rolled before being returned.
+    pub fn get(&self, path: &Path, roller: Option<&Roller>) -> std::io::Result<Arc<File>> {
+        let cached = self.cached(path);
+        let mut cached = cached.lock().unwrap_or_else(|error| error.into_inner());
+
+        let mut current = match metadata(path) {
+            Ok(metadata) => Some(metadata),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => None,
+            Err(error) => return Err(error),
+        };
+        if let (Some(roller), Some(metadata)) = (roller, current.as_ref()) {
+            if roller.should_roll_with(metadata.len(), metadata.modified()?) {
+                *cached = None;
+                roller.roll(path)?;
+                current = None;
+            }
+        }
+
+        if let (Some(open), Some(metadata)) = (cached.as_ref(), current.as_ref()) {
+            if open.identity == FileIdentity::from(metadata) {
+                return Ok(open.file.clone());
+            }
+        }
+
+        if let Some(parent) = path.parent() {
+            create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let identity = FileIdentity::from(&file.metadata()?);
+        let file = Arc::new(file);
+        *cached = Some(OpenFile {
+            file: file.clone(),
+            identity,
+        });
+        Ok(file)
+    }
+
+    /// The entry of the path. Closes idle and least recently used files to make room for it
+    fn cached(&self, path: &Path) -> Arc<Mutex<Option<OpenFile>>> {
+        let mut open = self.open.lock().unwrap_or_else(|error| error.into_inner());
+        let now = Instant::now();
+        if let Some(idle_timeout) = self.idle_timeout {
+            open.retain(|_, cached| now.duration_since(cached.last_used) < idle_timeout);
+        }
+        if let Some(cached) = open.get_mut(path) {
+            cached.last_used = now;
+            return cached.file.clone();
+        }
+        if open.len() >= self.max_open {
+            if let Some(oldest) = open
+                .iter()
+                .min_by_key(|(_, cached)| cached.last_used)
+                .map(|(path, _)| path.clone())
+            {
+                open.remove(&oldest);
+            }
+        }
+        let file = Arc::new(Mutex::new(None));
+        open.insert(
+            path.to_path_buf(),
+            CachedPath {
+                file: file.clone(),
+                last_used: now,
+            },
+        );
+        file
+    }
+
+    /// Flushes every open file to disk. The files are synced after releasing the locks
+    pub fn flush(&self) -> std::io::Result<()> {
+        let cached: Vec<_> = self
+            .open
+            .lock()
+            .unwrap_or_else(|error| error.into_inner())
+            .values()
+            .map(|cached| cached.file.clone())
+            .collect();
+        let files: Vec<Arc<File>> = cached
+            .iter()
+            .filter_map(|cached| {
+                let cached = cached.lock().unwrap_or_else(|error| error.into_inner());
+                cached.as_ref().map(|open| open.file.clone())
+            })
+            .collect();
+        for file in files {
+            file.sync_data()?;
+        }
+        Ok(())
+    }
+
+    /// The number of files currently held open
+    pub fn open_files(&self) -> usize {
+        self.open
+            .lock()
+            .unwrap_or_else(|error| error.into_inner())
+            .len()
+    }
+}
+
+/// Used to tell if the file at a path is still the file we have open
+#[derive(PartialEq, Eq)]
+struct FileIdentity {
+    #[cfg(unix)]
+    device: u64,
+    #[cfg(unix)]
+    inode: u64,
+    /// The file index needs an unstable API on Windows. So the creation time is used instead.
+    /// A file replaced within the same timestamp, or one Windows file tunneling gives the old
+    /// creation time, is not detected
+    #[cfg(not(unix))]
+    created: Option<std::time::SystemTime>,
+}
+
+impl From<&Metadata> for FileIdentity {
+    #[cfg(unix)]
+    fn from(metadata: &Metadata) -> Self {
+        use std::os::unix::fs::MetadataExt;
+        FileIdentity {
+            device: metadata.dev(),
+            inode: metadata.ino(),
+        }
+    }
+    #[cfg(not(unix))]
+    fn from(metadata: &Metadata) -> Self {
+        FileIdentity {
+            created: metadata.created().ok(),
+        }
+    }
+}
+
+/// A cached file handed to the LoggerWriter
+pub struct SharedFile(pub Arc<File>);
+
+impl Write for SharedFile {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        (&*self.0).write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        (&*self.0).flush()
+    }
+}
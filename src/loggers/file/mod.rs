@@ -0,0 +1,107 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use log::Record;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::config::FormatConfig;
+use crate::error::Error;
+use crate::filter::Filters;
+use crate::format::Format;
+use crate::loggers::target::LoggerTargetBuilder;
+use crate::loggers::{LoggerTarget, LoggerWriter};
+use crate::PlaceHolders;
+
+use self::handles::{FileHandles, SharedFile};
+use self::rolling::{Roller, RollingConfig};
+
+pub mod handles;
+pub mod rolling;
+
+pub struct FileLoggerBuilder;
+
+impl LoggerTargetBuilder for FileLoggerBuilder {
+    fn name(&self) -> &'static str {
+        "file_logger"
+    }
+
+    fn build(
+        &self,
+        value: Value,
+        placeholders: &PlaceHolders,
+        filters: &Filters,
+    ) -> Result<Box<dyn LoggerTarget>, Error> {
+        let file_config: FileConfig = serde_json::from_value(value)?;
+        let logger = FileLogger {
+            file_format: Format::with_filters(placeholders, filters, file_config.file, true)
+                .map_err(|error| error.in_config("file"))?,
+            roller: file_config.rolling.map(Roller::from),
+            handles: FileHandles::new(
+                file_config.max_open_files,
+                Some(Duration::from_secs(file_config.idle_timeout)).filter(|d| !d.is_zero()),
+            ),
+        };
+        Ok(Box::new(logger))
+    }
+}
+
+pub struct FileLogger {
+    pub file_format: Format,
+    pub roller: Option<Roller>,
+    pub handles: FileHandles,
+}
+
+impl LoggerTarget for FileLogger {
+    fn start_write<'log>(&'log self, record: &'log Record) -> anyhow::Result<LoggerWriter<'log>> {
+        let path = generate_path(&self.file_format, record);
+        let file = self.handles.get(&path, self.roller.as_ref())?;
+        Ok(LoggerWriter {
+            internal: Box::new(SharedFile(file)),
+            logger: Box::new(self),
+            record,
+        })
+    }
+
+    /// Files are never a terminal
+    fn supports_styles(&self, _record: &Record) -> bool {
+        false
+    }
+
+    fn flush(&self) -> anyhow::Result<()> {
+        self.handles.flush()?;
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct FileConfig {
+    #[serde(deserialize_with = "crate::config::format_config_string_or_struct")]
+    pub file: FormatConfig,
+    /// Rotate the file by size or time. Refer to [RollingConfig]
+    #[serde(default)]
+    pub rolling: Option<RollingConfig>,
+    /// How many files to keep open at once. Only matters if the file path uses placeholders or variables
+    #[serde(default = "default_max_open_files")]
+    pub max_open_files: usize,
+    /// Close files that have not been written to in this many seconds. 0 keeps them open
+    #[serde(default = "default_idle_timeout")]
+    pub idle_timeout: u64,
+}
+
+fn default_max_open_files() -> usize {
+    16
+}
+
+fn default_idle_timeout() -> u64 {
+    300
+}
+
+fn generate_path(format: &Format, record: &Record) -> PathBuf {
+    let mut path = String::new();
+    for values in format.format.iter() {
+        path.push_str(&values.build_message(record));
+    }
+    PathBuf::from(path)
+}
@@ -0,0 +1,183 @@
+use std::fs::{metadata, read_dir, remove_file, rename};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+
+use crate::time::{civil_from_days, epoch_seconds};
+
+/// Rolling settings for the file_logger target
+///
+/// ```json
+/// "rolling": { "max_size": 10485760, "interval": "daily", "suffix": "index", "max_files": 5 }
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RollingConfig {
+    /// Roll the active file once it reaches this many bytes
+    #[serde(default)]
+    pub max_size: Option<u64>,
+    /// Roll the active file once it was last written in an earlier period. Periods are in UTC
+    #[serde(default)]
+    pub interval: Option<RollingInterval>,
+    /// How archived files are named
+    #[serde(default)]
+    pub suffix: RollingSuffix,
+    /// How many archived files to keep. The oldest are deleted first
+    #[serde(default)]
+    pub max_files: Option<usize>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RollingInterval {
+    Hourly,
+    Daily,
+}
+
+impl RollingInterval {
+    fn seconds(&self) -> u64 {
+        match self {
+            RollingInterval::Hourly => 60 * 60,
+            RollingInterval::Daily => 60 * 60 * 24,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum RollingSuffix {
+    /// `app.log.1` is the newest archive
+    #[default]
+    Index,
+    /// `app.log.2022-01-31` named after the period the archive was written in
+    Timestamp,
+}
+
+/// Decides when the active file needs to be rolled and performs the roll.
+///
+/// The caller is responsible for making sure only one thread rolls a file at a time.
+pub struct Roller {
+    pub config: RollingConfig,
+}
+
+impl From<RollingConfig> for Roller {
+    fn from(config: RollingConfig) -> Self {
+        Roller { config }
+    }
+}
+
+impl Roller {
+    /// Checks the size and modified time of the active file against the config
+    pub(crate) fn should_roll_with(&self, len: u64, modified: SystemTime) -> bool {
+        if let Some(max_size) = self.config.max_size {
+            if len > 0 && len >= max_size {
+                return true;
+            }
+        }
+        if let Some(interval) = self.config.interval {
+            let period = |time: SystemTime| epoch_seconds(time) / interval.seconds();
+            if period(modified) < period(SystemTime::now()) {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Moves the active file to an archive and removes archives over the limit
+    pub fn roll(&self, path: &Path) -> std::io::Result<()> {
+        match self.config.suffix {
+            RollingSuffix::Index => self.roll_index(path),
+            RollingSuffix::Timestamp => self.roll_timestamp(path),
+        }
+    }
+
+    fn roll_index(&self, path: &Path) -> std::io::Result<()> {
+        if self.config.max_files == Some(0) {
+            return remove_file(path);
+        }
+        let mut last = 0;
+        while archive_path(path, &(last + 1).to_string()).exists() {
+            last += 1;
+        }
+        if let Some(max_files) = self.config.max_files {
+            while last >= max_files {
+                remove_file(archive_path(path, &last.to_string()))?;
+                last -= 1;
+            }
+        }
+        for index in (1..=last).rev() {
+            rename(
+                archive_path(path, &index.to_string()),
+                archive_path(path, &(index + 1).to_string()),
+            )?;
+        }
+        rename(path, archive_path(path, "1"))
+    }
+
+    fn roll_timestamp(&self, path: &Path) -> std::io::Result<()> {
+        let modified = metadata(path)?.modified()?;
+        let stamp = format_timestamp(epoch_seconds(modified), self.config.interval);
+        let mut target = archive_path(path, &stamp);
+        let mut duplicate = 1;
+        while target.exists() {
+            target = archive_path(path, &format!("{}.{}", stamp, duplicate));
+            duplicate += 1;
+        }
+        rename(path, target)?;
+        if let Some(max_files) = self.config.max_files {
+            let mut archives = list_archives(path)?;
+            if archives.len() > max_files {
+                archives.sort_by_key(|(_, modified)| *modified);
+                let remove = archives.len() - max_files;
+                for (archive, _) in archives.into_iter().take(remove) {
+                    remove_file(archive)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn archive_path(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".");
+    name.push(suffix);
+    path.with_file_name(name)
+}
+
+/// Every file next to the active file named `{file_name}.{suffix}`
+fn list_archives(path: &Path) -> std::io::Result<Vec<(PathBuf, SystemTime)>> {
+    let prefix = format!(
+        "{}.",
+        path.file_name().unwrap_or_default().to_string_lossy()
+    );
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let mut archives = Vec::new();
+    for entry in read_dir(parent)? {
+        let entry = entry?;
+        if entry.file_name().to_string_lossy().starts_with(&prefix) {
+            archives.push((entry.path(), entry.metadata()?.modified()?));
+        }
+    }
+    Ok(archives)
+}
+
+/// Formats the UTC time. The precision follows the interval so archives are named after their period
+fn format_timestamp(seconds: u64, interval: Option<RollingInterval>) -> String {
+    let (year, month, day) = civil_from_days((seconds / 86400) as i64);
+    let time = seconds % 86400;
+    let (hour, minute, second) = (time / 3600, time % 3600 / 60, time % 60);
+    match interval {
+        Some(RollingInterval::Daily) => format!("{:04}-{:02}-{:02}", year, month, day),
+        Some(RollingInterval::Hourly) => {
+            format!("{:04}-{:02}-{:02}-{:02}", year, month, day, hour)
+        }
+        None => format!(
+            "{:04}-{:02}-{:02}-{:02}-{:02}-{:02}",
+            year, month, day, hour, minute, second
+        ),
+    }
+}
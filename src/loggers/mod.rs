@@ -1,31 +1,69 @@
+use std::cell::Cell;
 use std::io::Write;
+use std::sync::Arc;
 
-use log::{Level, Record};
+use log::{Level, LevelFilter, Record};
 
-use crate::format::{Format, FormatSection};
+use crate::format::Format;
 use crate::kv::default_structure_dump::DefaultStructureDump;
 
-use crate::loggers::target::LoggerTarget;
+use crate::loggers::encoder::{json_line, Encoder};
+use crate::loggers::target::{LoggerOutput, LoggerTarget};
+use crate::loggers::tree::pattern::ModulePattern;
 use crate::loggers::writer::LoggerWriter;
+use crate::placeholder::with_styles;
 use crate::NitroLogger;
 
+pub mod color;
 pub mod console;
+pub mod encoder;
 pub mod file;
 pub mod target;
 pub mod tree;
 pub mod writer;
 
+thread_local! {
+    /// Reused to render every record logged on the thread
+    static RENDER_BUFFER: Cell<String> = const { Cell::new(String::new()) };
+}
+
+/// Larger buffers are dropped after the record instead of being kept for the thread
+const MAX_KEPT_CAPACITY: usize = 64 * 1024;
+
+/// Runs with the empty render buffer of the thread.
+/// The buffer is taken out while in use. So a record logged while rendering gets its own
+fn with_render_buffer<R>(f: impl FnOnce(&mut String) -> R) -> R {
+    let mut buffer = RENDER_BUFFER.with(Cell::take);
+    buffer.clear();
+    let result = f(&mut buffer);
+    if buffer.capacity() <= MAX_KEPT_CAPACITY {
+        RENDER_BUFFER.with(|cell| cell.set(buffer));
+    }
+    result
+}
+
 pub struct Logger {
     pub module: Option<String>,
+    /// Matched against the record target. A trailing `*` matches by prefix
+    pub target: Option<String>,
+    /// Set if the module is a glob or regex
+    pub module_pattern: Option<ModulePattern>,
     pub levels: Vec<Level>,
-    pub targets: Vec<Box<dyn LoggerTarget>>,
+    pub targets: Vec<LoggerOutput>,
     pub always_execute: bool,
+    /// `true` also sends records to the loggers of every parent module and the root loggers.
+    /// Unless a parent sets `false`. `false` stops the records here. Not set only sends to parents with `always_execute`
+    pub additive: Option<bool>,
     pub structure_dump: bool,
-    pub format: Format,
+    pub format: Arc<Format>,
+    pub encoder: Encoder,
 }
 
 impl Logger {
     pub fn module_matches(&self, module: &str) -> bool {
+        if let Some(pattern) = self.module_pattern.as_ref() {
+            return pattern.matches(module);
+        }
         if let Some(m) = self.module.as_ref() {
             if m.eq(module) {
                 return true;
@@ -33,55 +71,100 @@ impl Logger {
         }
         false
     }
+    /// If any target of the logger writes the level
+    pub fn accepts(&self, level: Level) -> bool {
+        if self.targets.is_empty() {
+            return self.levels.contains(&level);
+        }
+        self.targets
+            .iter()
+            .any(|output| output.accepts(level, &self.levels))
+    }
+    /// The most verbose level the logger accepts
+    pub fn max_level(&self) -> LevelFilter {
+        Level::iter()
+            .filter(|level| self.accepts(*level))
+            .map(|level| level.to_level_filter())
+            .max()
+            .unwrap_or(LevelFilter::Off)
+    }
     /// Logs a record
     /// Handling Formatting and the internal writers
+    ///
+    /// Targets using the format of the logger share one rendering. Targets with their own format are rendered separately
     pub fn log(&self, record: &Record, logger: &NitroLogger) {
-        let mut writers = Vec::new();
-        for target in self.targets.iter() {
-            if let Ok(value) = target.start_write(record) {
-                writers.push(value);
+        let level = record.level();
+        // Split by style support. Plain first then styled
+        let mut shared: [Vec<LoggerWriter>; 2] = [Vec::new(), Vec::new()];
+        for output in self.targets.iter() {
+            if output.format.is_none() && output.accepts(level, &self.levels) {
+                if let Ok(value) = output.target.start_write(record) {
+                    shared[output.styled(record) as usize].push(value);
+                }
             }
         }
-        for values in &self.format.format {
-            match values {
-                FormatSection::Text(value) => {
-                    self.write(&mut writers, value.as_bytes(), logger);
-                }
-                FormatSection::Variable(variable) => {
-                    self.write(&mut writers, variable.get_value(record.key_values()).as_bytes(), logger)
+        for (styled, writers) in shared.into_iter().enumerate() {
+            if !writers.is_empty() {
+                with_styles(styled == 1, || {
+                    self.write_record(record, &self.format, writers, logger)
+                });
+            }
+        }
+        for output in self.targets.iter() {
+            if let Some(format) = output.format.as_deref() {
+                if !output.accepts(level, &self.levels) {
+                    continue;
                 }
-                FormatSection::Placeholder(placeholder) => {
-                    self.write(
-                        &mut writers,
-                        placeholder.build_message(record).as_bytes(),
-                        logger,
-                    );
+                if let Ok(value) = output.target.start_write(record) {
+                    with_styles(output.styled(record), || {
+                        self.write_record(record, format, vec![value], logger)
+                    });
                 }
             }
         }
-        let mut writers = if self.structure_dump {
-            let mut dump = DefaultStructureDump { write: writers };
-            record.key_values().visit(&mut dump).unwrap();
-            dump.write
-        } else {
-            writers
-        };
-
-        self.write(&mut writers, "\n".as_bytes(), logger);
-
-        for mut writer in writers.into_iter() {
-            if let Err(error) = writer.flush() {
-                (logger.error_handler)(&anyhow::Error::from(error));
+    }
+    /// Renders the record once and hands the line to every writer in a single write
+    fn write_record<'log>(
+        &self,
+        record: &'log Record,
+        format: &Format,
+        writers: Vec<LoggerWriter<'log>>,
+        logger: &NitroLogger,
+    ) {
+        with_render_buffer(|line| {
+            self.render(record, format, line);
+            for mut writer in writers.into_iter() {
+                if let Err(error) = writer.write_all(line.as_bytes()) {
+                    (logger.error_handler)(&anyhow::Error::from(error));
+                }
+                if let Err(error) = writer.flush() {
+                    (logger.error_handler)(&anyhow::Error::from(error));
+                }
+                if let Err(error) = writer.logger.return_write(writer) {
+                    (logger.error_handler)(&error);
+                }
             }
-            if let Err(error) = writer.logger.return_write(writer) {
-                (logger.error_handler)(&error);
+        });
+    }
+    /// Encodes the record as one line. The format and structure dump or the JSON
+    fn render(&self, record: &Record, format: &Format, line: &mut String) {
+        match self.encoder {
+            Encoder::Text => {
+                format.render_into(record, line);
+                if self.structure_dump {
+                    let mut dump = DefaultStructureDump { write: line };
+                    record.key_values().visit(&mut dump).unwrap();
+                }
             }
+            Encoder::Json => line.push_str(&json_line(record)),
         }
+        line.push('\n');
     }
-    fn write(&self, writers: &mut [LoggerWriter], content: &[u8], logger: &NitroLogger) {
-        for writer in writers.iter_mut() {
-            if let Err(error) = writer.write_all(content) {
-                (logger.error_handler)(&anyhow::Error::from(error));
+    /// Flushes every target of the logger
+    pub fn flush(&self, logger: &NitroLogger) {
+        for output in self.targets.iter() {
+            if let Err(error) = output.target.flush() {
+                (logger.error_handler)(&error);
             }
         }
     }
@@ -1,7 +1,11 @@
+use std::sync::Arc;
+
+use crate::filter::Filters;
+use crate::format::Format;
 use crate::loggers::writer::LoggerWriter;
 use crate::loggers::{console, file};
 use crate::{Error, PlaceHolders};
-use log::Record;
+use log::{Level, Record};
 use serde_json::Value;
 
 pub type LoggerTargetBuilders = Vec<Box<dyn LoggerTargetBuilder>>;
@@ -15,17 +19,30 @@ pub fn default_logger_targets() -> LoggerTargetBuilders {
     logger_targets
 }
 
-pub trait LoggerTargetBuilder {
+pub trait LoggerTargetBuilder: Send + Sync {
     ///The name of the target
     fn name(&self) -> &'static str;
-    /// Creates a new LoggerTarget
+    /// Creates a new LoggerTarget. The placeholders and filters are the ones registered in
+    /// [LoggerBuilders](crate::LoggerBuilders) for any format the target has
     /// # Errors
     /// Errors for config issues
     fn build(
         &self,
         config: Value,
         placeholders: &PlaceHolders,
+        filters: &Filters,
     ) -> Result<Box<dyn LoggerTarget>, Error>;
+    /// Checks the config without side effects such as opening files.
+    /// By default this builds the target and drops it.
+    /// Override it if building the target has side effects
+    fn validate(
+        &self,
+        config: Value,
+        placeholders: &PlaceHolders,
+        filters: &Filters,
+    ) -> Result<(), Error> {
+        self.build(config, placeholders, filters).map(|_| ())
+    }
 }
 
 pub trait LoggerTarget: Sync + Send {
@@ -37,4 +54,55 @@ pub trait LoggerTarget: Sync + Send {
     fn return_write(&self, _: LoggerWriter) -> anyhow::Result<()> {
         Ok(())
     }
+
+    /// If styled placeholders should write ANSI codes for the record.
+    /// When false they write plain text. By default this returns true
+    fn supports_styles(&self, _record: &Record) -> bool {
+        true
+    }
+
+    /// Persists any buffered output. Called by `log::logger().flush()`
+    /// By default this function does nothing.
+    fn flush(&self) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+/// A target of a Logger with the overrides from its config
+#[derive(Clone)]
+pub struct LoggerOutput {
+    /// Shared with other loggers if the target is named in the config
+    pub target: Arc<dyn LoggerTarget>,
+    /// Used instead of the format of the logger
+    pub format: Option<Arc<Format>>,
+    /// Used instead of the levels of the logger
+    pub levels: Option<Vec<Level>>,
+    /// Styles forced on or off by the [ColorMode](crate::loggers::color::ColorMode). None asks the target
+    pub styles: Option<bool>,
+}
+
+impl LoggerOutput {
+    /// If the target writes the level. `logger_levels` are used if the target has no levels
+    pub fn accepts(&self, level: Level, logger_levels: &[Level]) -> bool {
+        self.levels
+            .as_deref()
+            .unwrap_or(logger_levels)
+            .contains(&level)
+    }
+    /// If styled placeholders should write ANSI codes for the record
+    pub fn styled(&self, record: &Record) -> bool {
+        self.styles
+            .unwrap_or_else(|| self.target.supports_styles(record))
+    }
+}
+
+impl From<Box<dyn LoggerTarget>> for LoggerOutput {
+    fn from(target: Box<dyn LoggerTarget>) -> Self {
+        LoggerOutput {
+            target: Arc::from(target),
+            format: None,
+            levels: None,
+            styles: None,
+        }
+    }
 }
@@ -1,8 +1,24 @@
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use log::{Level, LevelFilter};
+
 use crate::Logger;
 
+pub mod pattern;
+
+/// The most paths the level cache will hold. Paths past this are looked up every time
+const LEVEL_CACHE_LIMIT: usize = 4096;
+
 pub struct LoggerTree {
     pub loggers: Vec<Logger>,
     pub children: Vec<TreeNode>,
+    /// Loggers matched by the record target instead of the module path
+    pub targets: Vec<Logger>,
+    /// Loggers with a glob or regex module
+    pub patterns: Vec<Logger>,
+    /// The levels accepted for each path that has been checked. One bit per Level
+    level_cache: RwLock<HashMap<String, u8>>,
 }
 
 pub struct TreeNode {
@@ -16,48 +32,145 @@ impl LoggerTree {
         let mut logger_tree = LoggerTree {
             loggers: root,
             children: vec![],
+            targets: vec![],
+            patterns: vec![],
+            level_cache: RwLock::new(HashMap::new()),
         };
         for log in loggers {
-            let string = log
-                .module
-                .as_ref()
-                .expect("All Loggers Must have a module. Unless a root logger")
-                .clone();
-            logger_tree.add_node_lookup(log, string);
+            if log.target.is_some() {
+                logger_tree.targets.push(log);
+                continue;
+            }
+            if log.module_pattern.is_some() {
+                logger_tree.patterns.push(log);
+                continue;
+            }
+            match log.module.clone() {
+                Some(module) => logger_tree.add_node_lookup(log, module),
+                // create_loggers rejects these. Treat them as root loggers instead of panicking
+                None => logger_tree.loggers.push(log),
+            }
         }
         logger_tree
     }
-    pub fn find_logger(&self, path: &str) -> Option<Vec<&Logger>> {
-        let mut loggers = Vec::new();
-        let mut paths: Vec<&str> = path.split("::").collect();
-        if paths.is_empty() {
-            return None;
+    /// Finds the loggers for a record.
+    ///
+    /// Loggers with a matching target are used first. Otherwise the module path is looked up in the tree.
+    /// Records without a module path go to the root loggers
+    pub fn route(&self, target: &str, module_path: Option<&str>) -> Vec<&Logger> {
+        if let Some(loggers) = self.find_target_logger(target) {
+            return propagate(vec![self.loggers.iter().collect(), loggers]);
         }
-        let current_node = paths.remove(0);
-        for x in &self.children {
-            if x.module.eq_ignore_ascii_case(current_node) {
-                let found_loggers = x.find_logger(paths).unwrap();
-                for x in found_loggers {
-                    loggers.push(x);
-                }
-                for x in &self.loggers {
-                    if x.always_execute {
-                        loggers.push(x);
-                    }
-                }
-                return Some(loggers);
+        match module_path {
+            Some(module_path) => self.find_logger(module_path).unwrap_or_default(),
+            None => self.loggers.iter().collect(),
+        }
+    }
+    /// The target loggers matching the target. An exact match wins over a prefix. A longer prefix wins over a shorter one
+    pub fn find_target_logger(&self, target: &str) -> Option<Vec<&Logger>> {
+        let matches: Vec<(usize, &Logger)> = self
+            .targets
+            .iter()
+            .filter_map(|logger| {
+                let pattern = logger.target.as_deref()?;
+                target_specificity(pattern, target).map(|specificity| (specificity, logger))
+            })
+            .collect();
+        let best = matches.iter().map(|(specificity, _)| *specificity).max()?;
+        Some(
+            matches
+                .into_iter()
+                .filter(|(specificity, _)| *specificity == best)
+                .map(|(_, logger)| logger)
+                .collect(),
+        )
+    }
+    /// Finds the loggers for the module path.
+    ///
+    /// Precedence
+    /// 1. The deepest module logger. `my_app::db` over `my_app`
+    /// 2. The matching pattern loggers rank at the depth of the deepest one. `my_app::*::db` is at depth 3.
+    ///    A literal module at the same depth wins over them
+    /// 3. The root loggers
+    ///
+    /// The parent loggers are then added based on `additive`. Pattern loggers have the literal modules above them as parents
+    pub fn find_logger(&self, path: &str) -> Option<Vec<&Logger>> {
+        let mut levels = vec![self.loggers.iter().collect()];
+        collect_levels(&self.children, path.split("::"), &mut levels);
+        if let Some((depth, loggers)) = self.find_pattern_logger(path) {
+            let depth = depth.min(levels.len());
+            // A literal module at the same depth wins
+            let literal = levels.get(depth).is_some_and(|level| !level.is_empty());
+            if !literal {
+                levels.insert(depth, loggers);
             }
         }
-        for x in &self.loggers {
-            loggers.push(x);
+        Some(propagate(levels))
+    }
+    /// Every pattern logger matching the path in config order. With the depth of the deepest pattern
+    fn find_pattern_logger(&self, path: &str) -> Option<(usize, Vec<&Logger>)> {
+        let matches: Vec<(usize, &Logger)> = self
+            .patterns
+            .iter()
+            .filter_map(|logger| {
+                let pattern = logger.module_pattern.as_ref()?;
+                pattern.matches(path).then(|| (pattern.depth(), logger))
+            })
+            .collect();
+        let depth = matches.iter().map(|(depth, _)| *depth).max()?;
+        Some((
+            depth,
+            matches.into_iter().map(|(_, logger)| logger).collect(),
+        ))
+    }
+    /// Checks if any logger picked by [LoggerTree::route] accepts the level.
+    /// The answer is cached per target when the target is the module path. Which is the default for the `log` macros
+    pub fn accepts(&self, target: &str, module_path: Option<&str>, level: Level) -> bool {
+        let bit = 1u8 << level as usize;
+        if module_path != Some(target) {
+            return level_set(&self.route(target, module_path)) & bit != 0;
+        }
+        if let Some(levels) = self
+            .level_cache
+            .read()
+            .unwrap_or_else(|error| error.into_inner())
+            .get(target)
+        {
+            return levels & bit != 0;
+        }
+        let levels = level_set(&self.route(target, module_path));
+        let mut cache = self
+            .level_cache
+            .write()
+            .unwrap_or_else(|error| error.into_inner());
+        if cache.len() < LEVEL_CACHE_LIMIT {
+            cache.insert(target.to_string(), levels);
         }
-        Some(loggers)
+        levels & bit != 0
+    }
+    /// Every logger in the tree. Root loggers first
+    pub fn all_loggers(&self) -> Vec<&Logger> {
+        let mut loggers: Vec<&Logger> = self.loggers.iter().collect();
+        loggers.extend(self.targets.iter());
+        loggers.extend(self.patterns.iter());
+        for child in &self.children {
+            child.collect_loggers(&mut loggers);
+        }
+        loggers
+    }
+    /// The most verbose level accepted by any logger in the tree
+    pub fn max_level(&self) -> LevelFilter {
+        self.all_loggers()
+            .iter()
+            .map(|logger| logger.max_level())
+            .max()
+            .unwrap_or(LevelFilter::Off)
     }
     pub fn add_node_lookup(&mut self, logger: Logger, path: String) {
         let mut module_path: Vec<&str> = path.split("::").collect();
-        let current_node = module_path.get(0).unwrap();
+        let current_node = module_path.first().unwrap();
         for x in &mut self.children {
-            if x.module.eq(current_node) {
+            if x.module.eq_ignore_ascii_case(current_node) {
                 module_path.remove(0);
                 x.add_node_lookup(logger, module_path);
                 return;
@@ -77,44 +190,102 @@ impl LoggerTree {
     }
 }
 
-impl TreeNode {
-    pub fn find_logger(&self, mut path: Vec<&str>) -> Option<Vec<&Logger>> {
-        let mut loggers = Vec::new();
+/// Pushes the loggers of each node along the path. Stops at the first module without a node
+fn collect_levels<'tree, 'path>(
+    mut children: &'tree [TreeNode],
+    path: impl Iterator<Item = &'path str>,
+    levels: &mut Vec<Vec<&'tree Logger>>,
+) {
+    for module in path {
+        let Some(node) = children
+            .iter()
+            .find(|node| node.module.eq_ignore_ascii_case(module))
+        else {
+            break;
+        };
+        levels.push(node.loggers.iter().collect());
+        children = &node.children;
+    }
+}
 
-        if path.is_empty() {
-            for x in &self.loggers {
-                loggers.push(x);
-            }
-            return Some(loggers);
+/// Walks up from the deepest level with loggers. Levels are ordered from the root down.
+///
+/// `additive: true` adds every ancestor logger up to and including the root loggers.
+/// Unless an ancestor sets `additive: false`. Its loggers are added and the walk stops there.
+/// Not set only adds parents with `always_execute`. The parent then decides what its own parent adds.
+/// A level with both uses `false`
+fn propagate(levels: Vec<Vec<&Logger>>) -> Vec<&Logger> {
+    let mut levels = levels.into_iter().rev().filter(|level| !level.is_empty());
+    let Some(mut loggers) = levels.next() else {
+        return Vec::new();
+    };
+    let mut additive = additivity(&loggers);
+    for parent in levels {
+        match additive {
+            Some(false) => break,
+            Some(true) => loggers.extend(parent.iter().copied()),
+            None => loggers.extend(
+                parent
+                    .iter()
+                    .copied()
+                    .filter(|logger| logger.always_execute),
+            ),
         }
-        let current_node = path.remove(0);
-        for x in &self.children {
-            if x.module.eq_ignore_ascii_case(current_node) {
-                let found_loggers = x.find_logger(path).unwrap();
-                for x in found_loggers {
-                    loggers.push(x);
-                }
-                for x in &self.loggers {
-                    if x.always_execute {
-                        loggers.push(x);
-                    }
-                }
-                return Some(loggers);
-            }
-        }
-        for x in &self.loggers {
-            loggers.push(x);
+        additive = match (additive, additivity(&parent)) {
+            (_, Some(false)) => Some(false),
+            (None, parent) => parent,
+            (additive, _) => additive,
+        };
+    }
+    loggers
+}
+
+fn additivity(loggers: &[&Logger]) -> Option<bool> {
+    if loggers.iter().any(|logger| logger.additive == Some(false)) {
+        Some(false)
+    } else if loggers.iter().any(|logger| logger.additive == Some(true)) {
+        Some(true)
+    } else {
+        None
+    }
+}
+
+/// One bit per Level accepted by the loggers
+fn level_set(loggers: &[&Logger]) -> u8 {
+    Level::iter()
+        .filter(|level| loggers.iter().any(|logger| logger.accepts(*level)))
+        .fold(0u8, |levels, level| levels | 1 << level as usize)
+}
+
+/// How closely the target pattern matches. None if it does not match.
+/// `audit` only matches `audit`. `audit*` matches anything starting with `audit`
+fn target_specificity(pattern: &str, target: &str) -> Option<usize> {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => target.starts_with(prefix).then_some(prefix.len()),
+        None => (pattern == target).then_some(usize::MAX),
+    }
+}
+
+impl TreeNode {
+    pub fn find_logger(&self, path: Vec<&str>) -> Option<Vec<&Logger>> {
+        let mut levels = vec![self.loggers.iter().collect()];
+        collect_levels(&self.children, path.into_iter(), &mut levels);
+        Some(propagate(levels))
+    }
+    fn collect_loggers<'tree>(&'tree self, loggers: &mut Vec<&'tree Logger>) {
+        loggers.extend(self.loggers.iter());
+        for child in &self.children {
+            child.collect_loggers(loggers);
         }
-        Some(loggers)
     }
     pub fn add_node_lookup(&mut self, logger: Logger, mut path: Vec<&str>) -> bool {
         if path.is_empty() {
             self.add_logger(logger);
             return true;
         }
-        let current_node = path.get(0).unwrap();
+        let current_node = path.first().unwrap();
         for x in &mut self.children {
-            if x.module.eq(current_node) {
+            if x.module.eq_ignore_ascii_case(current_node) {
                 path.remove(0);
                 return x.add_node_lookup(logger, path);
             }
@@ -0,0 +1,63 @@
+use regex::Regex;
+
+/// A `module` that matches many modules
+///
+/// - `my_app::*::db` a `*` matches anything inside one segment of the path
+/// - `my_app::**::db` a `**` matches any number of segments
+/// - `regex:^my_app::v\d+$` a regex matched against the full module path
+///
+/// Globs also match every child of a matching module. Just like a literal module does.
+/// A regex is anchored so it has to match the whole path. Children are not matched
+#[derive(Debug, Clone)]
+pub struct ModulePattern {
+    pub pattern: String,
+    regex: Regex,
+}
+
+impl ModulePattern {
+    /// Checks if the module is a pattern
+    pub fn is_pattern(module: &str) -> bool {
+        module.starts_with("regex:") || module.contains('*')
+    }
+
+    pub fn new(pattern: &str) -> Result<ModulePattern, regex::Error> {
+        let regex = match pattern.strip_prefix("regex:") {
+            Some(regex) => Regex::new(&format!("^(?:{})$", regex))?,
+            None => Regex::new(&glob_to_regex(pattern))?,
+        };
+        Ok(ModulePattern {
+            pattern: pattern.to_string(),
+            regex,
+        })
+    }
+
+    pub fn matches(&self, module: &str) -> bool {
+        self.regex.is_match(module)
+    }
+
+    /// The number of `::` separated segments in the pattern. Used to rank it against literal modules
+    pub fn depth(&self) -> usize {
+        let pattern = self.pattern.strip_prefix("regex:").unwrap_or(&self.pattern);
+        pattern.matches("::").count() + 1
+    }
+}
+
+fn glob_to_regex(glob: &str) -> String {
+    let mut regex = String::from("^");
+    let mut rest = glob;
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix("**") {
+            regex.push_str(".*");
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix('*') {
+            regex.push_str("[^:]*");
+            rest = after;
+        } else {
+            let next = rest.find('*').unwrap_or(rest.len());
+            regex.push_str(&regex::escape(&rest[..next]));
+            rest = &rest[next..];
+        }
+    }
+    regex.push_str("(::.*)?$");
+    regex
+}
@@ -1,6 +1,6 @@
 use crate::placeholder::PlaceholderBuilder;
 use crate::{Error, Placeholder};
-use chrono::Local;
+use chrono::{DateTime, Local};
 use log::Record;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -31,7 +31,8 @@ pub struct ChronoPlaceholder {
 
 impl Placeholder for ChronoPlaceholder {
     fn build_message<'message>(&'message self, _: &'message Record) -> Cow<'message, str> {
-        Cow::Owned(Local::now().format(&self.config.format).to_string())
+        let time = DateTime::<Local>::from(crate::placeholder::record_time());
+        Cow::Owned(time.format(&self.config.format).to_string())
     }
 
     fn settings(&self) -> Option<Value> {
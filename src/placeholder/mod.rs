@@ -6,6 +6,8 @@ pub mod standard_placeholders;
 pub mod style_term;
 
 use std::borrow::Cow;
+use std::cell::Cell;
+use std::time::SystemTime;
 
 use crate::Error;
 use log::Record;
@@ -13,6 +15,40 @@ use serde::de::DeserializeOwned;
 use serde_json::Value;
 use std::fmt::Debug;
 
+thread_local! {
+    static STYLES_ENABLED: Cell<bool> = const { Cell::new(true) };
+}
+
+/// If styled placeholders should write ANSI codes. False while rendering for a target without style support
+pub fn styles_enabled() -> bool {
+    STYLES_ENABLED.with(Cell::get)
+}
+
+/// Renders with styles turned on or off for the current thread
+pub(crate) fn with_styles<R>(enabled: bool, render: impl FnOnce() -> R) -> R {
+    let previous = STYLES_ENABLED.with(|styles| styles.replace(enabled));
+    let result = render();
+    STYLES_ENABLED.with(|styles| styles.set(previous));
+    result
+}
+
+thread_local! {
+    static RECORD_TIME: Cell<Option<SystemTime>> = const { Cell::new(None) };
+}
+
+/// When the record being rendered was logged. Records written by the async writer thread keep the time of the `log!` call
+pub fn record_time() -> SystemTime {
+    RECORD_TIME.with(Cell::get).unwrap_or_else(SystemTime::now)
+}
+
+/// Renders with the time the record was logged for the current thread
+pub(crate) fn with_record_time<R>(time: SystemTime, render: impl FnOnce() -> R) -> R {
+    let previous = RECORD_TIME.with(|record_time| record_time.replace(Some(time)));
+    let result = render();
+    RECORD_TIME.with(|record_time| record_time.set(previous));
+    result
+}
+
 pub type PlaceHolders = Vec<Box<dyn PlaceholderBuilder>>;
 
 #[allow(unused_mut)]
@@ -22,13 +58,14 @@ pub fn default_placeholders() -> PlaceHolders {
         Box::new(standard_placeholders::LevelPlaceHolderBuilder {}),
         Box::new(standard_placeholders::ModulePlaceHolderBuilder {}),
         Box::new(standard_placeholders::EnvironmentPlaceholderBuilder {}),
+        Box::new(standard_placeholders::TextPlaceholderBuilder {}),
     ];
     #[cfg(feature = "chrono")]
     placeholders.push(Box::new(chrono::ChronoPlaceHolderBuilder {}));
     placeholders
 }
 
-pub trait PlaceholderBuilder {
+pub trait PlaceholderBuilder: Send + Sync {
     /// The name of the placeholder
     fn name<'message>(&self) -> &'message str;
     /// Create a new Placeholder
@@ -6,7 +6,7 @@ use log::Record;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::fmt::Debug;
-use std::path::MAIN_SEPARATOR;
+use std::path::MAIN_SEPARATOR_STR;
 
 pub struct MessagePlaceholderBuilder;
 
@@ -33,6 +33,38 @@ impl Placeholder for MessagePlaceholder {
     }
 }
 
+pub struct TextPlaceholderBuilder;
+
+impl PlaceholderBuilder for TextPlaceholderBuilder {
+    fn name<'message>(&self) -> &'message str {
+        "text"
+    }
+
+    fn build(&self, value: Option<Value>) -> Result<Box<dyn Placeholder>, Error> {
+        let settings: TextPlaceholderSettings = super::parse_config_no_default(value)?;
+        Ok(Box::new(TextPlaceholder(settings.value)))
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TextPlaceholderSettings {
+    pub value: String,
+}
+
+/// Literal text. Used to give text a style
+#[derive(Debug)]
+pub struct TextPlaceholder(pub String);
+
+impl Placeholder for TextPlaceholder {
+    fn build_message<'message>(&'message self, _record: &'message Record) -> Cow<'message, str> {
+        Cow::Borrowed(self.0.as_str())
+    }
+
+    fn settings(&self) -> Option<Value> {
+        Some(serde_json::json!({ "value": self.0 }))
+    }
+}
+
 pub struct LevelPlaceHolderBuilder;
 
 impl PlaceholderBuilder for LevelPlaceHolderBuilder {
@@ -45,10 +77,11 @@ impl PlaceholderBuilder for LevelPlaceHolderBuilder {
         {
             let value: LevelPlaceholderSettings = super::parse_config_no_default(_value)?;
 
-            if !value.path && value.styles.is_some() {
-                Ok(Box::new(super::style_term::level::StyledLevelPlaceholder::from(value.styles.unwrap())))
-            } else {
-                Ok(Box::new(LevelPlaceHolder))
+            match value.styles {
+                Some(styles) if !value.path => Ok(Box::new(
+                    super::style_term::level::StyledLevelPlaceholder::from(styles),
+                )),
+                _ => Ok(Box::new(LevelPlaceHolder)),
             }
         }
         #[cfg(not(feature = "style-term"))]
@@ -85,16 +118,81 @@ impl PlaceholderBuilder for ModulePlaceHolderBuilder {
     }
 
     fn build(&self, value: Option<Value>) -> Result<Box<dyn Placeholder>, Error> {
-        if let Some(value) = value {
-            if value
-                .get("path")
-                .and_then(|value| value.as_bool())
-                .unwrap_or(false)
-            {
-                return Ok(Box::new(PathModulePlaceHolder {}));
+        let settings: ModulePlaceholderSettings = super::parse_config(value)?;
+        let separator = if settings.path {
+            MAIN_SEPARATOR_STR
+        } else {
+            "::"
+        };
+        match settings.abbreviate {
+            Some(Abbreviate::Enabled(false)) | None if settings.path => {
+                Ok(Box::new(PathModulePlaceHolder {}))
             }
+            Some(Abbreviate::Enabled(false)) | None => Ok(Box::new(ModulePlaceHolder {})),
+            Some(Abbreviate::Enabled(true)) => Ok(Box::new(AbbreviatedModulePlaceHolder {
+                max_length: 0,
+                separator,
+            })),
+            Some(Abbreviate::MaxLength(max_length)) => Ok(Box::new(AbbreviatedModulePlaceHolder {
+                max_length,
+                separator,
+            })),
         }
-        Ok(Box::new(ModulePlaceHolder {}))
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct ModulePlaceholderSettings {
+    #[serde(default)]
+    pub path: bool,
+    /// `true` shortens `my_app::server::handler` to `m::s::handler`
+    #[serde(default)]
+    pub abbreviate: Option<Abbreviate>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+#[serde(untagged)]
+pub enum Abbreviate {
+    Enabled(bool),
+    /// Only shortens the parents from the left until the module fits
+    MaxLength(usize),
+}
+
+/// Shortens each parent module to its first character. The last module is kept
+#[derive(Debug)]
+pub struct AbbreviatedModulePlaceHolder {
+    pub max_length: usize,
+    pub separator: &'static str,
+}
+
+impl Placeholder for AbbreviatedModulePlaceHolder {
+    fn build_message<'message>(&'message self, record: &'message Record) -> Cow<'message, str> {
+        let module = record.module_path().unwrap_or("");
+        let mut parts: Vec<&str> = module.split("::").collect();
+        let last = parts.len() - 1;
+        // The length once joined with the separator
+        let mut length = module.len() - last * "::".len() + last * self.separator.len();
+        if length <= self.max_length {
+            return Cow::Owned(parts.join(self.separator));
+        }
+        for part in &mut parts[..last] {
+            if length <= self.max_length {
+                break;
+            }
+            if let Some(first) = part.chars().next() {
+                length -= part.len() - first.len_utf8();
+                *part = &part[..first.len_utf8()];
+            }
+        }
+        Cow::Owned(parts.join(self.separator))
+    }
+
+    fn settings(&self) -> Option<Value> {
+        serde_json::to_value(ModulePlaceholderSettings {
+            path: self.separator != "::",
+            abbreviate: Some(Abbreviate::MaxLength(self.max_length)),
+        })
+        .ok()
     }
 }
 
@@ -119,7 +217,7 @@ impl Placeholder for PathModulePlaceHolder {
             record
                 .module_path()
                 .unwrap_or("")
-                .replace("::", &MAIN_SEPARATOR.to_string()),
+                .replace("::", MAIN_SEPARATOR_STR),
         )
     }
     fn settings(&self) -> Option<Value> {
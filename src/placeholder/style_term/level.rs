@@ -3,7 +3,7 @@ use std::fmt::{Debug};
 use log::{Level, Record};
 use serde::{Serialize, Deserialize};
 use serde_json::Value;
-use style_term::{ DefaultColor, StylesContainer, StyleString};
+use style_term::{ DefaultColor, StylesContainer};
 use crate::Placeholder;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -72,13 +72,17 @@ impl From<LevelColorConfig> for StyledLevelPlaceholder {
 
 impl Placeholder for StyledLevelPlaceholder {
     fn build_message<'message>(&'message self, record: &'message Record) -> Cow<'message, str> {
-        let value = match record.level() {
-            Level::Error => { "Error".apply_styles(&self.0.error).to_string() }
-            Level::Warn => { "Warn".apply_styles(&self.0.warn).to_string() }
-            Level::Info => { "Info".apply_styles(&self.0.info).to_string() }
-            Level::Debug => { "Debug".apply_styles(&self.0.debug).to_string() }
-            Level::Trace => { "Trace".apply_styles(&self.0.trace).to_string() }
+        let (name, styles) = match record.level() {
+            Level::Error => ("Error", &self.0.error),
+            Level::Warn => ("Warn", &self.0.warn),
+            Level::Info => ("Info", &self.0.info),
+            Level::Debug => ("Debug", &self.0.debug),
+            Level::Trace => ("Trace", &self.0.trace),
         };
+        if !crate::placeholder::styles_enabled() {
+            return Cow::Borrowed(name);
+        }
+        let value = super::apply_styles(name, styles);
         Cow::Owned(value)
     }
 
@@ -1 +1,107 @@
-pub mod level;
\ No newline at end of file
+use std::fmt::{Display, Formatter, Write};
+
+use serde::Deserialize;
+use serde_json::Value;
+use style_term::{Color, DefaultColor, DisplayColor, Style, Styles, StylesContainer};
+
+pub mod level;
+
+/// Wraps the content in the ANSI codes of the styles.
+///
+/// Unlike the Display of style_term this does not check `NO_COLOR` or `TERM`.
+/// That is decided per target by [ColorMode](crate::loggers::color::ColorMode)
+pub fn apply_styles(content: &str, styles: &StylesContainer) -> String {
+    let mut value = String::with_capacity(content.len() + 16);
+    let _ = write!(value, "{}", Ansi(styles));
+    value.push_str(content);
+    let _ = write!(value, "{}", Style::from(Styles::Reset));
+    value
+}
+
+/// The codes that start the styles
+struct Ansi<'styles>(&'styles StylesContainer);
+
+impl Display for Ansi<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for style in &self.0.styles {
+            Display::fmt(style, f)?;
+        }
+        if let Some(background) = self.0.background_color.as_ref() {
+            background.fmt_background(f)?;
+        }
+        if let Some(color) = self.0.text_color.as_ref() {
+            color.fmt_color(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// The `style` setting. Colors can be a name such as `cyan` or `bright_red`. Or a style_term Color
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct StyleConfig {
+    #[serde(default)]
+    text_color: Option<ColorConfig>,
+    #[serde(default)]
+    background_color: Option<ColorConfig>,
+    #[serde(default)]
+    styles: Vec<Style>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ColorConfig {
+    Name(String),
+    Color(Color),
+}
+
+impl TryFrom<ColorConfig> for Color {
+    type Error = String;
+
+    fn try_from(value: ColorConfig) -> Result<Self, Self::Error> {
+        match value {
+            ColorConfig::Color(color) => Ok(color),
+            ColorConfig::Name(name) => color_by_name(&name)
+                .map(Color::from)
+                .ok_or_else(|| format!("Unknown color `{}`", name)),
+        }
+    }
+}
+
+fn color_by_name(name: &str) -> Option<DefaultColor> {
+    let name: String = name
+        .chars()
+        .filter(|c| *c != '_' && *c != '-')
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+    let color = match name.as_str() {
+        "black" => DefaultColor::Black,
+        "red" => DefaultColor::Red,
+        "green" => DefaultColor::Green,
+        "yellow" => DefaultColor::Yellow,
+        "blue" => DefaultColor::Blue,
+        "magenta" => DefaultColor::Magenta,
+        "cyan" => DefaultColor::Cyan,
+        "white" => DefaultColor::White,
+        "gray" | "grey" => DefaultColor::Gray,
+        "brightred" => DefaultColor::BrightRed,
+        "brightgreen" => DefaultColor::BrightGreen,
+        "brightyellow" => DefaultColor::BrightYellow,
+        "brightblue" => DefaultColor::BrightBlue,
+        "brightmagenta" => DefaultColor::BrightMagenta,
+        "brightcyan" => DefaultColor::BrightCyan,
+        "brightwhite" => DefaultColor::BrightWhite,
+        _ => return None,
+    };
+    Some(color)
+}
+
+/// Parses the `style` setting of a format section
+pub fn parse_style(value: Value) -> Result<StylesContainer, String> {
+    let config: StyleConfig = serde_json::from_value(value).map_err(|error| error.to_string())?;
+    Ok(StylesContainer {
+        text_color: config.text_color.map(Color::try_from).transpose()?,
+        background_color: config.background_color.map(Color::try_from).transpose()?,
+        styles: config.styles,
+    })
+}
@@ -0,0 +1,75 @@
+use std::fs::metadata;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, SystemTime};
+
+use crate::config::Config;
+use crate::error::Error;
+use crate::LoggerHandle;
+
+/// Polls a config file and reloads the logger when it changes.
+/// Created by [LoggerHandle::watch]. Watching stops when this is dropped
+pub struct ConfigWatcher {
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl ConfigWatcher {
+    pub(crate) fn start(
+        handle: LoggerHandle,
+        path: PathBuf,
+        interval: Duration,
+    ) -> Result<ConfigWatcher, Error> {
+        let mut last_modified = modified(&path)?;
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_stop = stop.clone();
+        let thread = thread::Builder::new()
+            .name("nitro_log_watcher".to_string())
+            .spawn(move || loop {
+                thread::park_timeout(interval);
+                if thread_stop.load(Ordering::Relaxed) {
+                    return;
+                }
+                let result = match modified(&path) {
+                    Ok(modified) if modified != last_modified => {
+                        last_modified = modified;
+                        Config::from_path(&path).and_then(|config| handle.reload(config))
+                    }
+                    Ok(_) => Ok(()),
+                    Err(error) => Err(error),
+                };
+                if let Err(error) = result {
+                    (handle.logger.error_handler)(&anyhow::Error::from(error));
+                }
+            })?;
+        Ok(ConfigWatcher {
+            stop,
+            thread: Some(thread),
+        })
+    }
+
+    /// Stops watching and waits for the thread to exit
+    pub fn stop(mut self) {
+        self.join();
+    }
+
+    fn join(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            thread.thread().unpark();
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for ConfigWatcher {
+    fn drop(&mut self) {
+        self.join();
+    }
+}
+
+fn modified(path: &Path) -> Result<SystemTime, Error> {
+    Ok(metadata(path)?.modified()?)
+}
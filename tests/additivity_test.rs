@@ -0,0 +1,109 @@
+use std::sync::Arc;
+
+use log::Level;
+use nitro_log::config::{create_loggers, Config};
+use nitro_log::loggers::tree::LoggerTree;
+use nitro_log::LoggerBuilders;
+use serde_json::json;
+
+fn tree(loggers: serde_json::Value) -> LoggerTree {
+    let config: Config = serde_json::from_value(json!({
+        "root_loggers": [
+            { "module": "root", "format": "root {{message({})}}", "targets": [{ "type": "console" }] }
+        ],
+        "loggers": loggers
+    }))
+    .unwrap();
+    let (root, loggers) = create_loggers(config, &LoggerBuilders::default()).unwrap();
+    LoggerTree::new(root, loggers)
+}
+
+fn modules<'tree>(tree: &'tree LoggerTree, path: &str) -> Vec<&'tree str> {
+    tree.find_logger(path)
+        .unwrap()
+        .into_iter()
+        .map(|logger| logger.module.as_deref().unwrap())
+        .collect()
+}
+
+#[test]
+fn additive() {
+    let tree = tree(json!([
+        { "module": "my_app", "targets": [] },
+        { "module": "my_app::db", "additive": true, "targets": [] },
+        { "module": "my_app::http", "targets": [] },
+        { "module": "other", "additive": true, "targets": [] }
+    ]));
+    // Additive goes up to the root loggers even though my_app does not set it
+    assert_eq!(
+        modules(&tree, "my_app::db"),
+        vec!["my_app::db", "my_app", "root"]
+    );
+    assert_eq!(modules(&tree, "my_app::http"), vec!["my_app::http"]);
+    assert_eq!(modules(&tree, "other::thing"), vec!["other", "root"]);
+}
+
+#[test]
+fn stopped_by_parent() {
+    let tree = tree(json!([
+        { "module": "my_app", "additive": false, "targets": [] },
+        { "module": "my_app::db", "targets": [] },
+        { "module": "my_app::db::pool", "additive": true, "targets": [] }
+    ]));
+    assert_eq!(
+        modules(&tree, "my_app::db::pool"),
+        vec!["my_app::db::pool", "my_app::db", "my_app"]
+    );
+}
+
+#[test]
+fn blocks_always_execute() {
+    let tree = tree(json!([
+        { "module": "my_app", "always_execute": true, "targets": [] },
+        { "module": "my_app::db", "targets": [] },
+        { "module": "my_app::http", "additive": false, "targets": [] }
+    ]));
+    assert_eq!(modules(&tree, "my_app::db"), vec!["my_app::db", "my_app"]);
+    assert_eq!(modules(&tree, "my_app::http"), vec!["my_app::http"]);
+}
+
+#[test]
+fn inherit_targets() {
+    let tree = tree(json!([
+        { "module": "my_app", "format": "my_app {{message({})}}", "targets": [{ "type": "console" }] },
+        { "module": "my_app::db", "level": "error", "inherit_targets": true, "targets": [] },
+        { "module": "my_app::db::pool", "level": "trace", "inherit_targets": true, "targets": [] },
+        { "module": "other", "level": "warn", "inherit_targets": true, "targets": [] },
+        { "module": "My_App::Http", "inherit_targets": true, "targets": [] }
+    ]));
+    let my_app = tree.find_logger("my_app").unwrap();
+    let db = tree.find_logger("my_app::db").unwrap();
+    assert_eq!(db.len(), 1);
+    assert_eq!(db[0].module.as_deref(), Some("my_app::db"));
+    assert_eq!(db[0].levels, vec![Level::Error]);
+    assert_eq!(db[0].targets.len(), 1);
+    // The built target is shared instead of opened again
+    assert!(Arc::ptr_eq(
+        &db[0].targets[0].target,
+        &my_app[0].targets[0].target
+    ));
+    assert!(Arc::ptr_eq(&db[0].format, &my_app[0].format));
+    // Inherits from my_app::db which inherits from my_app
+    let pool = tree.find_logger("my_app::db::pool").unwrap();
+    assert_eq!(pool.len(), 1);
+    assert_eq!(pool[0].targets.len(), 1);
+    assert_eq!(pool[0].max_level(), log::LevelFilter::Trace);
+    // Without a parent module logger the root loggers are used
+    let other = tree.find_logger("other").unwrap();
+    assert_eq!(other.len(), 1);
+    assert_eq!(other[0].module.as_deref(), Some("other"));
+    assert_eq!(other[0].levels, vec![Level::Error, Level::Warn]);
+    assert_eq!(other[0].targets.len(), 1);
+    // Parents are matched without case like the tree lookup
+    let http = tree.find_logger("my_app::http").unwrap();
+    assert_eq!(http[0].module.as_deref(), Some("My_App::Http"));
+    assert!(Arc::ptr_eq(
+        &http[0].targets[0].target,
+        &my_app[0].targets[0].target
+    ));
+}
@@ -0,0 +1,124 @@
+use std::borrow::Cow;
+
+mod common;
+
+use log::{Level, Record};
+use nitro_log::error::Error;
+use nitro_log::filter::standard_filters::{Align, PadFilter};
+use nitro_log::filter::Filter;
+use nitro_log::placeholder::standard_placeholders::AbbreviatedModulePlaceHolder;
+use nitro_log::placeholder::Placeholder;
+use nitro_log::LoggerBuilders;
+
+fn render_level(format: &str, level: Level) -> Result<String, Error> {
+    let format = common::format(&LoggerBuilders::default(), format)?;
+    let kvs = [("user", "Admin")];
+    let record = Record::builder()
+        .args(format_args!("Hello World"))
+        .level(level)
+        .module_path(Some("my_app::server::handler"))
+        .key_values(&kvs)
+        .build();
+    Ok(common::render(&format, &record))
+}
+
+fn render(format: &str) -> Result<String, Error> {
+    render_level(format, Level::Info)
+}
+
+#[test]
+fn shorthand() {
+    assert_eq!(render("[{{level({}):>5}}]").unwrap(), "[ INFO]");
+    assert_eq!(
+        render_level("[{{level({}):>5}}]", Level::Error).unwrap(),
+        "[ERROR]"
+    );
+    // Without `()` it would pad the variable instead of the placeholder
+    match render("[{{level:>5}}]").unwrap_err() {
+        Error::InvalidFormat {
+            column, message, ..
+        } => {
+            assert_eq!(column, 2);
+            assert_eq!(
+                message,
+                "`level` is a placeholder. Use `{{level({}):>5}}` to pad it"
+            );
+        }
+        error => panic!("Unexpected error {}", error),
+    }
+    assert_eq!(render("[{{user:8}}]").unwrap(), "[Admin   ]");
+    assert_eq!(render("[{{user:.^9 | upper}}]").unwrap(), "[..ADMIN..]");
+    assert_eq!(render("[{{message({}):>3}}]").unwrap(), "[Hello World]");
+}
+
+#[test]
+fn settings() {
+    assert_eq!(
+        render(r#"[{{level({"width": 7, "align": "center", "fill": "-"})}}]"#).unwrap(),
+        "[-INFO--]"
+    );
+    assert_eq!(
+        render(r#"[{{message({"width": 13})}}]"#).unwrap(),
+        "[Hello World  ]"
+    );
+    // The shorthand wins
+    assert_eq!(
+        render(r#"[{{message({"width": 13}):>12}}]"#).unwrap(),
+        "[ Hello World]"
+    );
+    // Settings are only for placeholders
+    assert!(matches!(
+        render(r#"{{levle({"width": 5})}}"#).unwrap_err(),
+        Error::UnknownPlaceholder { .. }
+    ));
+    match render(r#"{{level({"align": "middle"})}}"#).unwrap_err() {
+        Error::InvalidPlaceholderSettings { placeholder, .. } => assert_eq!(placeholder, "level"),
+        error => panic!("Unexpected error {}", error),
+    }
+}
+
+#[test]
+fn ignores_styles() {
+    let pad = PadFilter {
+        width: 6,
+        fill: ' ',
+        align: Align::Right,
+    };
+    assert_eq!(
+        pad.apply(Cow::Borrowed("\u{1b}[31mInfo\u{1b}[0m")),
+        "  \u{1b}[31mInfo\u{1b}[0m"
+    );
+}
+
+#[test]
+fn module_abbreviation() {
+    assert_eq!(
+        render(r#"{{module({"abbreviate": true})}}"#).unwrap(),
+        "m::s::handler"
+    );
+    assert_eq!(
+        render(r#"{{module({"abbreviate": 20})}}"#).unwrap(),
+        "m::server::handler"
+    );
+    assert_eq!(
+        render(r#"{{module({"abbreviate": 30})}}"#).unwrap(),
+        "my_app::server::handler"
+    );
+    assert_eq!(
+        render(r#"[{{module({"abbreviate": true}):<15}}]"#).unwrap(),
+        "[m::s::handler  ]"
+    );
+    assert_eq!(
+        render(r#"{{module({"abbreviate": false})}}"#).unwrap(),
+        "my_app::server::handler"
+    );
+    // The length is measured with the separator
+    let record = Record::builder()
+        .module_path(Some("my_app::server::handler"))
+        .build();
+    let placeholder = AbbreviatedModulePlaceHolder {
+        max_length: 21,
+        separator: "/",
+    };
+    assert_eq!(placeholder.build_message(&record), "my_app/server/handler");
+}
@@ -0,0 +1,49 @@
+mod common;
+
+use std::sync::mpsc;
+use std::time::Duration;
+
+use common::{RecordingBuilder, Writes};
+use log::info;
+use nitro_log::config::Config;
+use nitro_log::{LoggerBuilders, NitroLogger};
+use serde_json::json;
+
+fn config() -> Config {
+    serde_json::from_value(json!({
+        "async": { "capacity": 1, "overflow": "block" },
+        "root_loggers": [{ "format": "{{message({})}}", "targets": [{ "type": "recording" }] }]
+    }))
+    .unwrap()
+}
+
+/// A target logging from the writer thread while the queue is full
+#[test]
+fn test() {
+    let writes = Writes::default();
+    let mut builders = LoggerBuilders::default();
+    let target = RecordingBuilder::new(&[&writes]).on_write(|record| {
+        if !record.args().to_string().starts_with("inner") {
+            info!("inner 1");
+            // The queue is full. Would wait for the writer thread forever
+            info!("inner 2");
+        }
+    });
+    builders.targets.push(Box::new(target));
+    let handle = NitroLogger::load(config(), builders).unwrap();
+
+    let (sender, receiver) = mpsc::channel();
+    std::thread::spawn(move || {
+        info!("first");
+        log::logger().flush();
+        sender.send(()).unwrap();
+    });
+    receiver
+        .recv_timeout(Duration::from_secs(5))
+        .expect("The writer thread is blocked on its own queue");
+    assert_eq!(common::content(&writes), "first\ninner 1\n");
+    assert_eq!(handle.dropped_records(), 1);
+
+    // A logger is already installed
+    assert!(NitroLogger::load(config(), LoggerBuilders::default()).is_err());
+}
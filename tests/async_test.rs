@@ -0,0 +1,41 @@
+use log::info;
+use nitro_log::{LoggerBuilders, NitroLogger};
+use serde_json::json;
+
+#[test]
+fn test() {
+    let directory = std::env::temp_dir().join(format!("nitro_log_async_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&directory);
+    let file = directory.join("app.log");
+    let config = json!({
+        "async": { "capacity": 8, "overflow": "block" },
+        "root_loggers": [
+            {
+                "format": "{{message({})}} {{value}}",
+                "targets": [
+                    {
+                        "type": "file_logger",
+                        "properties": {
+                            "file": file.to_str().unwrap()
+                        }
+                    }
+                ]
+            }
+        ]
+    });
+    let handle = NitroLogger::load(
+        serde_json::from_value(config).unwrap(),
+        LoggerBuilders::default(),
+    )
+    .unwrap();
+    for i in 0..100 {
+        info!(value = i; "Message");
+    }
+
+    log::logger().flush();
+    let content = std::fs::read_to_string(&file).unwrap();
+    assert_eq!(content.lines().count(), 100);
+    assert_eq!(content.lines().last(), Some("Message 99"));
+    assert_eq!(handle.dropped_records(), 0);
+    std::fs::remove_dir_all(&directory).unwrap();
+}
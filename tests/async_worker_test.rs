@@ -0,0 +1,72 @@
+mod common;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use common::{RecordingBuilder, Writes};
+use log::info;
+use nitro_log::{LoggerBuilders, NitroLogger};
+use serde_json::{json, Value};
+
+/// Milliseconds into the day of an RFC 3339 timestamp
+fn milliseconds(timestamp: &str) -> u64 {
+    let time = &timestamp[11..23];
+    let parts: Vec<u64> = time
+        .split([':', '.'])
+        .map(|part| part.parse().unwrap())
+        .collect();
+    ((parts[0] * 60 + parts[1]) * 60 + parts[2]) * 1000 + parts[3]
+}
+
+#[test]
+fn test() {
+    let writes = Writes::default();
+    let mut builders = LoggerBuilders::default();
+    // Slow to write. Panics on the message `panic`
+    let slow = RecordingBuilder::new(&[&writes]).on_write(|record| {
+        if record.args().to_string() == "panic" {
+            panic!("The target failed");
+        }
+        std::thread::sleep(Duration::from_millis(30));
+    });
+    builders.targets.push(Box::new(slow));
+    let config = json!({
+        "async": { "capacity": 16, "overflow": "block" },
+        "root_loggers": [{ "encoder": "json", "targets": [{ "type": "recording" }] }]
+    });
+    static ERRORS: AtomicUsize = AtomicUsize::new(0);
+    let handle = NitroLogger::load_with_error_handler(
+        serde_json::from_value(config).unwrap(),
+        builders,
+        Box::new(|_| {
+            ERRORS.fetch_add(1, Ordering::SeqCst);
+        }),
+    )
+    .unwrap();
+    info!("first");
+    info!("panic");
+    info!("second");
+    info!("third");
+    // Would hang if the panic stopped the writer thread
+    log::logger().flush();
+
+    let records: Vec<Value> = common::content(&writes)
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    let messages: Vec<&str> = records
+        .iter()
+        .map(|record| record["message"].as_str().unwrap())
+        .collect();
+    assert_eq!(messages, ["first", "second", "third"]);
+    assert_eq!(ERRORS.load(Ordering::SeqCst), 1);
+    assert_eq!(handle.dropped_records(), 0);
+
+    // The records were logged together but written 30ms apart. The timestamp is when they were logged
+    let first = milliseconds(records[0]["timestamp"].as_str().unwrap());
+    let third = milliseconds(records[2]["timestamp"].as_str().unwrap());
+    // Wraps at midnight
+    const DAY: u64 = 24 * 60 * 60 * 1000;
+    let elapsed = (third + DAY - first) % DAY;
+    assert!(elapsed < 30, "{} {}", first, third);
+}
@@ -0,0 +1,68 @@
+use nitro_log::loggers::color::ColorMode;
+
+/// One test so the environment variables are not changed by another test at the same time
+#[test]
+fn forced() {
+    std::env::remove_var("NO_COLOR");
+    std::env::remove_var("CLICOLOR_FORCE");
+    assert_eq!(ColorMode::Auto.forced(), None);
+    assert_eq!(ColorMode::Always.forced(), Some(true));
+    assert_eq!(ColorMode::Never.forced(), Some(false));
+
+    std::env::set_var("CLICOLOR_FORCE", "0");
+    assert_eq!(ColorMode::Auto.forced(), None);
+    std::env::set_var("CLICOLOR_FORCE", "1");
+    assert_eq!(ColorMode::Auto.forced(), Some(true));
+
+    // NO_COLOR wins over CLICOLOR_FORCE
+    std::env::set_var("NO_COLOR", "1");
+    assert_eq!(ColorMode::Auto.forced(), Some(false));
+    assert_eq!(ColorMode::Always.forced(), Some(true));
+    std::env::set_var("NO_COLOR", "");
+    assert_eq!(ColorMode::Auto.forced(), Some(true));
+
+    std::env::remove_var("NO_COLOR");
+    std::env::remove_var("CLICOLOR_FORCE");
+    #[cfg(feature = "style-term")]
+    file_targets();
+}
+
+/// Files are plain unless the color mode is always
+#[cfg(feature = "style-term")]
+fn file_targets() {
+    use log::info;
+    use nitro_log::{LoggerBuilders, NitroLogger};
+    use serde_json::json;
+
+    let directory = std::env::temp_dir().join(format!("nitro_log_color_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&directory);
+    let (plain, colored) = (directory.join("plain.log"), directory.join("colored.log"));
+    let config = json!({
+        "root_loggers": [{
+            "format": {
+                "format": "{{level(colors)}} {{message({})}}",
+                "placeholders": { "colors": { "styles": {} } }
+            },
+            "targets": [
+                { "type": "file_logger", "properties": { "file": plain.to_str().unwrap() } },
+                {
+                    "type": "file_logger",
+                    "properties": { "file": colored.to_str().unwrap() },
+                    "color": "always"
+                }
+            ]
+        }]
+    });
+    NitroLogger::load(
+        serde_json::from_value(config).unwrap(),
+        LoggerBuilders::default(),
+    )
+    .unwrap();
+    info!("Hello");
+
+    assert_eq!(std::fs::read_to_string(&plain).unwrap(), "Info Hello\n");
+    let colored = std::fs::read_to_string(&colored).unwrap();
+    assert!(colored.starts_with("\u{1b}["));
+    assert!(colored.ends_with("Info\u{1b}[0m Hello\n"));
+    std::fs::remove_dir_all(&directory).unwrap();
+}
@@ -0,0 +1,121 @@
+//! Fixtures shared by the integration tests
+#![allow(dead_code)]
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+use log::Record;
+use nitro_log::error::Error;
+use nitro_log::filter::Filters;
+use nitro_log::format::Format;
+use nitro_log::loggers::target::{LoggerTarget, LoggerTargetBuilder};
+use nitro_log::loggers::writer::LoggerWriter;
+use nitro_log::placeholder::PlaceHolders;
+use nitro_log::LoggerBuilders;
+use serde_json::Value;
+
+/// Every call to `write`
+pub type Writes = Arc<Mutex<Vec<String>>>;
+
+/// Called before each record is written
+pub type OnWrite = Arc<dyn Fn(&Record) + Send + Sync>;
+
+/// The writes joined together
+pub fn content(writes: &Writes) -> String {
+    writes.lock().unwrap().concat()
+}
+
+pub struct RecordingWriter(Writes);
+
+impl Write for RecordingWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let value = String::from_utf8(buf.to_vec()).unwrap();
+        self.0.lock().unwrap().push(value);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+pub struct RecordingTarget {
+    writes: Writes,
+    styled: bool,
+    on_write: Option<OnWrite>,
+}
+
+impl LoggerTarget for RecordingTarget {
+    fn start_write<'log>(&'log self, record: &'log Record) -> anyhow::Result<LoggerWriter<'log>> {
+        if let Some(on_write) = &self.on_write {
+            on_write(record);
+        }
+        Ok(LoggerWriter {
+            internal: Box::new(RecordingWriter(self.writes.clone())),
+            record,
+            logger: Box::new(self),
+        })
+    }
+
+    fn supports_styles(&self, _record: &Record) -> bool {
+        self.styled
+    }
+}
+
+/// The `recording` target.
+///
+/// The `output` property picks the index into `outputs`. `styled: false` turns off styles
+pub struct RecordingBuilder {
+    pub outputs: Vec<Writes>,
+    pub on_write: Option<OnWrite>,
+}
+
+impl RecordingBuilder {
+    pub fn new(outputs: &[&Writes]) -> RecordingBuilder {
+        RecordingBuilder {
+            outputs: outputs.iter().map(|writes| (*writes).clone()).collect(),
+            on_write: None,
+        }
+    }
+
+    pub fn on_write(mut self, on_write: impl Fn(&Record) + Send + Sync + 'static) -> Self {
+        self.on_write = Some(Arc::new(on_write));
+        self
+    }
+}
+
+impl LoggerTargetBuilder for RecordingBuilder {
+    fn name(&self) -> &'static str {
+        "recording"
+    }
+
+    fn build(
+        &self,
+        config: Value,
+        _placeholders: &PlaceHolders,
+        _filters: &Filters,
+    ) -> Result<Box<dyn LoggerTarget>, Error> {
+        let output = config["output"].as_u64().unwrap_or_default() as usize;
+        Ok(Box::new(RecordingTarget {
+            writes: self.outputs[output].clone(),
+            styled: config["styled"].as_bool().unwrap_or(true),
+            on_write: self.on_write.clone(),
+        }))
+    }
+}
+
+/// Builds the format with the placeholders and filters of the builders
+pub fn format(builders: &LoggerBuilders, format: &str) -> Result<Format, Error> {
+    Format::with_filters(
+        &builders.placeholders,
+        &builders.filters,
+        nitro_log::config::FormatConfig::from(format.to_string()),
+        false,
+    )
+}
+
+/// Renders the record the same way the loggers do
+pub fn render(format: &Format, record: &Record) -> String {
+    let mut buffer = String::new();
+    format.render_into(record, &mut buffer);
+    buffer
+}
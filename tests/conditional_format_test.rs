@@ -0,0 +1,95 @@
+mod common;
+
+use log::{Level, Record};
+use nitro_log::error::Error;
+use nitro_log::format::Format;
+use nitro_log::LoggerBuilders;
+
+fn format(format: &str) -> Result<Format, Error> {
+    common::format(&LoggerBuilders::default(), format)
+}
+
+fn render(format: &Format, kvs: &[(&str, &str)]) -> String {
+    let record = Record::builder()
+        .args(format_args!("Hello"))
+        .level(Level::Info)
+        .key_values(&kvs)
+        .build();
+    common::render(format, &record)
+}
+
+#[test]
+fn conditional() {
+    let format =
+        format("[{{level({})}}]{{#if request_id}} req={{request_id}}{{/if}}: {{message({})}}")
+            .unwrap();
+    assert_eq!(render(&format, &[]), "[INFO]: Hello");
+    assert_eq!(
+        render(&format, &[("request_id", "abc")]),
+        "[INFO] req=abc: Hello"
+    );
+}
+
+#[test]
+fn nested() {
+    let format = format("{{#if a}}a{{#if b}} b{{/if}};{{/if}}").unwrap();
+    assert_eq!(render(&format, &[("b", "1")]), "");
+    assert_eq!(render(&format, &[("a", "1")]), "a;");
+    assert_eq!(render(&format, &[("a", "1"), ("b", "1")]), "a b;");
+}
+
+#[test]
+fn default() {
+    let format = format(r#"{{ user | default("anon") }} {{ user.id | default(0) }}"#).unwrap();
+    assert_eq!(render(&format, &[]), "anon 0");
+    assert_eq!(render(&format, &[("user", "admin")]), "admin 0");
+}
+
+#[test]
+fn path_values() {
+    let format = format("{{user.id}} {{user.name}} {{user.roles}} {{user.team}}").unwrap();
+    let user = serde_json::json!({ "id": 7, "name": "ana", "roles": ["admin"], "team": null });
+    let kvs = [("user", log::kv::Value::from_serde(&user))];
+    let record = Record::builder()
+        .args(format_args!("Hello"))
+        .level(Level::Info)
+        .key_values(&kvs)
+        .build();
+    assert_eq!(
+        common::render(&format, &record),
+        r#"7 ana ["admin"] {undefined}"#
+    );
+}
+
+#[test]
+fn errors() {
+    let error = |value: &str| match format(value).err().unwrap() {
+        Error::InvalidFormat {
+            column, message, ..
+        } => (column, message),
+        error => panic!("Unexpected error {}", error),
+    };
+    assert_eq!(
+        error("a {{#if x}} b"),
+        (3, "Unclosed `{{#if}}`. Expected `{{/if}}`".to_string())
+    );
+    assert_eq!(
+        error("a {{/if}}"),
+        (3, "`{{/if}}` without an open `{{#if}}`".to_string())
+    );
+    assert_eq!(
+        error("{{message({}) | default(1)}}"),
+        (17, "`default` only applies to variables".to_string())
+    );
+    assert_eq!(
+        error(r#"{{x | upper | default("x")}}"#),
+        (15, "`default` must be the first filter".to_string())
+    );
+    assert_eq!(
+        error("{{x | default}}"),
+        (7, "`default` takes one argument".to_string())
+    );
+    let (column, message) = error("{{x | shout}}");
+    assert_eq!(column, 7);
+    assert!(message.starts_with("Unknown filter `shout`. Known filters: default, upper"));
+}
@@ -0,0 +1,150 @@
+use nitro_log::config::{create_loggers, Config};
+use nitro_log::error::Error;
+use nitro_log::LoggerBuilders;
+use serde_json::json;
+
+fn build(config: serde_json::Value) -> Error {
+    let config: Config = serde_json::from_value(config).unwrap();
+    create_loggers(config, &LoggerBuilders::default())
+        .err()
+        .expect("Config should fail to build")
+}
+
+#[test]
+fn unknown_target() {
+    let error = build(json!({
+        "root_loggers": [
+            { "format": "{{message({})}}", "targets": [{ "type": "console" }, { "type": "consle" }] }
+        ]
+    }));
+    match &error {
+        Error::UnknownTarget { name, known, path } => {
+            assert_eq!(name, "consle");
+            assert!(known.contains(&"console".to_string()));
+            assert_eq!(path, "root_loggers[0].targets[1].type");
+        }
+        error => panic!("Unexpected error {}", error),
+    }
+    assert!(error
+        .to_string()
+        .contains("Known targets: console, file_logger"));
+}
+
+#[test]
+fn unknown_placeholder() {
+    let error = build(json!({
+        "root_loggers": [
+            { "format": "{{mesage({})}}", "targets": [] }
+        ]
+    }));
+    match error {
+        Error::UnknownPlaceholder { name, known, path } => {
+            assert_eq!(name, "mesage");
+            assert!(known.contains(&"message".to_string()));
+            assert_eq!(path, "root_loggers[0].format");
+        }
+        error => panic!("Unexpected error {}", error),
+    }
+}
+
+#[test]
+fn invalid_placeholder_settings() {
+    let error = build(json!({
+        "root_loggers": [],
+        "loggers": [
+            {
+                "module": "my_app",
+                "format": {
+                    "format": "{{env(HOME)}}",
+                    "placeholders": { "HOME": { "name": "HOME" } }
+                },
+                "targets": []
+            }
+        ]
+    }));
+    match error {
+        Error::InvalidPlaceholderSettings {
+            placeholder,
+            path,
+            message,
+        } => {
+            assert_eq!(placeholder, "env");
+            assert_eq!(path, "loggers[0].format.placeholders.HOME");
+            assert!(message.contains("key"), "{}", message);
+        }
+        error => panic!("Unexpected error {}", error),
+    }
+}
+
+#[test]
+fn invalid_target_settings() {
+    let error = build(json!({
+        "root_loggers": [
+            {
+                "targets": [
+                    { "type": "file_logger", "properties": { "file": "logs/app.log", "rolling": { "interval": "weekly" } } }
+                ]
+            }
+        ]
+    }));
+    match error {
+        Error::InvalidTargetSettings { target, path, .. } => {
+            assert_eq!(target, "file_logger");
+            assert_eq!(path, "root_loggers[0].targets[0].properties");
+        }
+        error => panic!("Unexpected error {}", error),
+    }
+}
+
+#[test]
+fn missing_module() {
+    let error = build(json!({
+        "root_loggers": [],
+        "loggers": [{ "targets": [] }]
+    }));
+    assert!(matches!(error, Error::MissingModule { path } if path == "loggers[0]"));
+}
+
+#[test]
+fn root_logger_target() {
+    let error = build(json!({
+        "root_loggers": [
+            { "format": "{{message({})}}", "targets": [] },
+            { "target": "sql", "format": "{{message({})}}", "targets": [] }
+        ]
+    }));
+    assert!(matches!(error, Error::RootLoggerTarget { path } if path == "root_loggers[1]"));
+}
+
+#[test]
+fn validate_reports_every_problem() {
+    let config: Config = serde_json::from_value(json!({
+        "root_loggers": [
+            { "format": "{{message({})}}", "targets": [{ "type": "console" }] }
+        ],
+        "loggers": [
+            { "module": "my_app::db", "format": "{{levle({})}}", "targets": [{ "type": "consle" }] },
+            { "module": "my_app::web", "targets": [{ "type": "file_logger", "properties": {} }] }
+        ]
+    }))
+    .unwrap();
+    let problems = config
+        .validate(&LoggerBuilders::default())
+        .expect_err("Config should be invalid");
+    assert_eq!(problems.len(), 3);
+    assert_eq!(problems[0].logger, "loggers[0]");
+    assert_eq!(problems[0].module.as_deref(), Some("my_app::db"));
+    assert!(matches!(problems[0].error, Error::UnknownTarget { .. }));
+    assert!(matches!(
+        problems[1].error,
+        Error::UnknownPlaceholder { .. }
+    ));
+    assert_eq!(problems[2].module.as_deref(), Some("my_app::web"));
+    assert!(matches!(
+        problems[2].error,
+        Error::InvalidTargetSettings { .. }
+    ));
+    assert!(problems[2]
+        .to_string()
+        .starts_with("loggers[1] (my_app::web): "));
+}
@@ -0,0 +1,90 @@
+use nitro_log::config::Config;
+use nitro_log::LoggerBuilders;
+
+const JSON: &str = r#"{
+  "root_loggers": [
+    {
+      "format": "[{{level({})}}] {{message({})}}",
+      "levels": ["INFO", "WARN", "ERROR"],
+      "targets": [{ "type": "console" }]
+    }
+  ],
+  "loggers": [
+    {
+      "module": "my_app::db",
+      "format": {
+        "format": "{{env(HOME)}} {{message({})}}",
+        "placeholders": { "HOME": { "key": "HOME" } }
+      },
+      "targets": [{ "type": "file_logger", "properties": { "file": "logs/db.log" } }]
+    }
+  ]
+}"#;
+
+fn assert_same_as_json(config: Config) {
+    let expected = serde_json::to_value(Config::from_json_str(JSON).unwrap()).unwrap();
+    assert!(config.validate(&LoggerBuilders::default()).is_ok());
+    assert_eq!(serde_json::to_value(config).unwrap(), expected);
+}
+
+#[test]
+#[cfg(feature = "toml")]
+fn toml() {
+    let config = Config::from_toml_str(
+        r#"
+[[root_loggers]]
+format = "[{{level({})}}] {{message({})}}"
+levels = ["INFO", "WARN", "ERROR"]
+targets = [{ type = "console" }]
+
+[[loggers]]
+module = "my_app::db"
+targets = [{ type = "file_logger", properties = { file = "logs/db.log" } }]
+
+[loggers.format]
+format = "{{env(HOME)}} {{message({})}}"
+placeholders = { HOME = { key = "HOME" } }
+"#,
+    )
+    .unwrap();
+    assert_same_as_json(config);
+}
+
+#[test]
+#[cfg(feature = "yaml")]
+fn yaml() {
+    let config = Config::from_yaml_str(
+        r#"
+root_loggers:
+  - format: "[{{level({})}}] {{message({})}}"
+    levels: [INFO, WARN, ERROR]
+    targets:
+      - type: console
+loggers:
+  - module: my_app::db
+    format:
+      format: "{{env(HOME)}} {{message({})}}"
+      placeholders:
+        HOME:
+          key: HOME
+    targets:
+      - type: file_logger
+        properties:
+          file: logs/db.log
+"#,
+    )
+    .unwrap();
+    assert_same_as_json(config);
+}
+
+#[test]
+fn from_path() {
+    let file = std::env::temp_dir().join(format!("nitro_log_config_{}.json", std::process::id()));
+    std::fs::write(&file, JSON).unwrap();
+    assert_same_as_json(Config::from_path(&file).unwrap());
+    std::fs::remove_file(&file).unwrap();
+    assert!(matches!(
+        Config::from_path("Cargo.lock"),
+        Err(nitro_log::error::Error::UnsupportedConfigFile(_))
+    ));
+}
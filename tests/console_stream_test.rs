@@ -0,0 +1,66 @@
+#[cfg(feature = "style-term")]
+mod common;
+
+use log::Level;
+use nitro_log::loggers::console::{ConsoleConfig, ConsoleStream};
+use serde_json::json;
+
+#[test]
+fn config() {
+    let config: ConsoleConfig = serde_json::from_value(json!({ "stream": "split" })).unwrap();
+    assert_eq!(config.stream, ConsoleStream::Split);
+    let config: ConsoleConfig = serde_json::from_value(json!({})).unwrap();
+    assert_eq!(config.stream, ConsoleStream::Stdout);
+    assert!(serde_json::from_value::<ConsoleConfig>(json!({ "stream": "stdin" })).is_err());
+}
+
+#[test]
+fn split() {
+    let split = ConsoleStream::Split;
+    assert!(split.is_stderr(Level::Error));
+    assert!(split.is_stderr(Level::Warn));
+    assert!(!split.is_stderr(Level::Info));
+    assert!(!split.is_stderr(Level::Trace));
+    assert!(ConsoleStream::Stderr.is_stderr(Level::Debug));
+    assert!(!ConsoleStream::Stdout.is_stderr(Level::Error));
+}
+
+/// Styled placeholders write plain text to targets without style support
+#[cfg(feature = "style-term")]
+mod styles {
+    use log::info;
+    use nitro_log::{LoggerBuilders, NitroLogger};
+    use serde_json::json;
+
+    use crate::common::{self, RecordingBuilder, Writes};
+
+    #[test]
+    fn degrade() {
+        std::env::remove_var("NO_COLOR");
+        std::env::remove_var("CLICOLOR_FORCE");
+        let (styled, plain) = (Writes::default(), Writes::default());
+        let mut builders = LoggerBuilders::default();
+        builders
+            .targets
+            .push(Box::new(RecordingBuilder::new(&[&styled, &plain])));
+        let config = json!({
+            "root_loggers": [{
+                "format": {
+                    "format": "{{level(colors)}} {{message({})}}",
+                    "placeholders": { "colors": { "styles": {} } }
+                },
+                "targets": [
+                    { "type": "recording" },
+                    { "type": "recording", "properties": { "output": 1, "styled": false } }
+                ]
+            }]
+        });
+        NitroLogger::load(serde_json::from_value(config).unwrap(), builders).unwrap();
+        info!("Hello");
+
+        assert_eq!(common::content(&plain), "Info Hello\n");
+        let styled = common::content(&styled);
+        assert!(styled.contains('\u{1b}'));
+        assert!(styled.ends_with("Hello\n"));
+    }
+}
@@ -0,0 +1,39 @@
+use log::log_enabled;
+use log::Level::{Debug, Info, Trace};
+use nitro_log::{LoggerBuilders, NitroLogger};
+use serde_json::json;
+
+mod database {
+    use log::log_enabled;
+    use log::Level::{Debug, Trace};
+
+    pub fn check() {
+        assert!(log_enabled!(Debug));
+        assert!(!log_enabled!(Trace));
+    }
+}
+
+#[test]
+fn test() {
+    let config = json!({
+        "root_loggers": [
+            { "level": "info", "targets": [] }
+        ],
+        "loggers": [
+            { "module": "enabled_test::database", "level": "debug", "targets": [] }
+        ]
+    });
+    NitroLogger::load(
+        serde_json::from_value(config).unwrap(),
+        LoggerBuilders::default(),
+    )
+    .unwrap();
+    assert!(log_enabled!(Info));
+    assert!(!log_enabled!(Debug));
+    // Checked twice so the cached answer is used
+    assert!(!log_enabled!(Debug));
+    database::check();
+    database::check();
+    assert!(log_enabled!(target: "enabled_test::database", Debug));
+    assert!(!log_enabled!(target: "enabled_test::database", Trace));
+}
@@ -0,0 +1,85 @@
+use std::sync::Arc;
+
+use log::Level;
+use log::LevelFilter;
+use nitro_log::config::{create_loggers, Config};
+use nitro_log::env::EnvOverrides;
+use nitro_log::loggers::tree::LoggerTree;
+use nitro_log::LoggerBuilders;
+use serde_json::json;
+
+fn config() -> Config {
+    serde_json::from_value(json!({
+        "root_loggers": [
+            { "format": "{{message({})}}", "targets": [{ "type": "console" }] }
+        ],
+        "loggers": [
+            { "module": "my_crate::db", "levels": ["ERROR"], "targets": [] }
+        ]
+    }))
+    .unwrap()
+}
+
+#[test]
+fn parse() {
+    let overrides =
+        EnvOverrides::parse(Some("warn"), Some("my_crate::db=trace, hyper=error,tokio")).unwrap();
+    assert_eq!(overrides.level, Some(LevelFilter::Warn));
+    assert_eq!(
+        overrides.modules,
+        vec![
+            ("my_crate::db".to_string(), LevelFilter::Trace),
+            ("hyper".to_string(), LevelFilter::Error),
+            ("tokio".to_string(), LevelFilter::Trace),
+        ]
+    );
+    assert!(EnvOverrides::parse(Some("loud"), None).is_err());
+    assert!(EnvOverrides::parse(None, Some("=info")).is_err());
+}
+
+#[test]
+fn apply() {
+    let mut config = config();
+    EnvOverrides::parse(Some("warn"), Some("my_crate::db=debug,hyper=error"))
+        .unwrap()
+        .apply(&mut config);
+    assert_eq!(config.root_loggers[0].level, Some(LevelFilter::Warn));
+    assert_eq!(
+        config.loggers[0].resolved_levels(),
+        vec![Level::Error, Level::Warn, Level::Info, Level::Debug]
+    );
+    // hyper did not have a logger so it inherits the root logger
+    let hyper = &config.loggers[1];
+    assert_eq!(hyper.module.as_deref(), Some("hyper"));
+    assert_eq!(hyper.resolved_levels(), vec![Level::Error]);
+    assert!(hyper.inherit_targets);
+
+    // The root target is shared instead of built again
+    let (root, loggers) = create_loggers(config, &LoggerBuilders::default()).unwrap();
+    let tree = LoggerTree::new(root, loggers);
+    let hyper = tree.find_logger("hyper").unwrap();
+    assert_eq!(hyper.len(), 1);
+    assert_eq!(hyper[0].levels, vec![Level::Error]);
+    assert!(Arc::ptr_eq(
+        &hyper[0].targets[0].target,
+        &tree.loggers[0].targets[0].target
+    ));
+}
+
+#[test]
+fn case() {
+    let mut config = config();
+    EnvOverrides::parse(None, Some("My_Crate::DB=debug"))
+        .unwrap()
+        .apply(&mut config);
+    assert_eq!(config.loggers.len(), 1);
+    assert_eq!(config.loggers[0].level, Some(LevelFilter::Debug));
+}
+
+#[test]
+fn from_env() {
+    std::env::set_var("NITRO_LOG_LEVEL", "off");
+    let config = config().with_env_overrides().unwrap();
+    std::env::remove_var("NITRO_LOG_LEVEL");
+    assert!(config.root_loggers[0].resolved_levels().is_empty());
+}
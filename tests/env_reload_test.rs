@@ -0,0 +1,47 @@
+mod common;
+
+use common::{RecordingBuilder, Writes};
+use log::{info, LevelFilter};
+use nitro_log::config::Config;
+use nitro_log::env::EnvOverrides;
+use nitro_log::{LoggerBuilders, NitroLogger};
+use serde_json::json;
+
+fn config() -> Config {
+    serde_json::from_value(json!({
+        "root_loggers": [
+            { "level": "error", "format": "{{message({})}}", "targets": [{ "type": "recording" }] }
+        ]
+    }))
+    .unwrap()
+}
+
+/// The overrides read at startup survive a reload
+#[test]
+fn test() {
+    let writes = Writes::default();
+    let mut builders = LoggerBuilders::default();
+    builders
+        .targets
+        .push(Box::new(RecordingBuilder::new(&[&writes])));
+    let mut loaded = config();
+    EnvOverrides::parse(Some("info"), None)
+        .unwrap()
+        .apply(&mut loaded);
+    let handle = NitroLogger::load(loaded, builders).unwrap();
+    info!("First");
+
+    handle.reload(config()).unwrap();
+    assert_eq!(log::max_level(), LevelFilter::Info);
+    info!("Second");
+
+    // A config with its own overrides uses them
+    let mut overridden = config();
+    EnvOverrides::parse(Some("warn"), None)
+        .unwrap()
+        .apply(&mut overridden);
+    handle.reload(overridden).unwrap();
+    assert_eq!(log::max_level(), LevelFilter::Warn);
+    info!("Third");
+    assert_eq!(common::content(&writes), "First\nSecond\n");
+}
@@ -0,0 +1,39 @@
+use log::info;
+use nitro_log::{LoggerBuilders, NitroLogger};
+use serde_json::json;
+
+#[test]
+fn test() {
+    let directory = std::env::temp_dir().join(format!("nitro_log_handles_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&directory);
+    let file = directory.join("app.log");
+    let config = json!({
+        "root_loggers": [
+            {
+                "format": "{{message({})}}",
+                "targets": [
+                    {
+                        "type": "file_logger",
+                        "properties": {
+                            "file": file.to_str().unwrap()
+                        }
+                    }
+                ]
+            }
+        ]
+    });
+    NitroLogger::load(
+        serde_json::from_value(config).unwrap(),
+        LoggerBuilders::default(),
+    )
+    .unwrap();
+    info!("First");
+    info!("Second");
+    assert_eq!(std::fs::read_to_string(&file).unwrap(), "First\nSecond\n");
+
+    // Simulate an external rotator moving the file away
+    std::fs::rename(&file, directory.join("app.log.old")).unwrap();
+    info!("Third");
+    assert_eq!(std::fs::read_to_string(&file).unwrap(), "Third\n");
+    std::fs::remove_dir_all(&directory).unwrap();
+}
@@ -0,0 +1,140 @@
+use std::borrow::Cow;
+
+mod common;
+
+use log::{Level, Log, Record};
+use nitro_log::config::{create_loggers, Config};
+use nitro_log::error::Error;
+use nitro_log::filter::standard_filters::{LowerFilter, TruncateFilter, UpperFilter};
+use nitro_log::filter::{Filter, FilterBuilder};
+use nitro_log::loggers::tree::LoggerTree;
+use nitro_log::{LoggerBuilders, NitroLogger};
+use serde_json::{json, Value};
+
+/// Reverses the value
+struct ReverseFilterBuilder;
+
+impl FilterBuilder for ReverseFilterBuilder {
+    fn name(&self) -> &'static str {
+        "reverse"
+    }
+
+    fn build(&self, _args: Vec<Value>) -> Result<Box<dyn Filter>, Error> {
+        Ok(Box::new(ReverseFilter))
+    }
+}
+
+#[derive(Debug)]
+struct ReverseFilter;
+
+impl Filter for ReverseFilter {
+    fn apply<'value>(&self, value: Cow<'value, str>) -> Cow<'value, str> {
+        Cow::Owned(value.chars().rev().collect())
+    }
+}
+
+fn render(format: &str) -> Result<String, Error> {
+    let mut builders = LoggerBuilders::default();
+    builders.filters.push(Box::new(ReverseFilterBuilder));
+    let format = common::format(&builders, format)?;
+    let kvs = [("user", "Admin"), ("text", "line\n\"two\"\t\\")];
+    let record = Record::builder()
+        .args(format_args!("Hello World"))
+        .level(Level::Info)
+        .module_path(Some("my_app::db"))
+        .key_values(&kvs)
+        .build();
+    Ok(common::render(&format, &record))
+}
+
+#[test]
+fn standard() {
+    assert_eq!(
+        render("{{user | upper}} {{user | lower}}").unwrap(),
+        "ADMIN admin"
+    );
+    assert_eq!(
+        render("{{message({}) | truncate(5) | upper}}").unwrap(),
+        "HELLO"
+    );
+    assert_eq!(
+        render(r#"[{{level({}) | pad_left(5)}}] [{{user | pad_right(7, ".")}}] [{{user | pad_left(2)}}]"#)
+            .unwrap(),
+        "[ INFO] [Admin..] [Admin]"
+    );
+    assert_eq!(render("{{text | escape}}").unwrap(), r#"line\n"two"\t\\"#);
+    assert_eq!(render("{{text | json}}").unwrap(), r#""line\n\"two\"\t\\""#);
+}
+
+#[test]
+fn custom() {
+    assert_eq!(
+        render(r#"{{missing | default("anon") | reverse | upper}}"#).unwrap(),
+        "NONA"
+    );
+}
+
+#[test]
+fn invalid_arguments() {
+    let error = |format: &str| match render(format).unwrap_err() {
+        Error::InvalidFormat {
+            column, message, ..
+        } => (column, message),
+        error => panic!("Unexpected error {}", error),
+    };
+    assert_eq!(
+        error("{{user | truncate}}"),
+        (10, "`truncate` requires the argument `length`".to_string())
+    );
+    assert_eq!(
+        error(r#"{{user | truncate("a")}}"#)
+            .1
+            .split(':')
+            .next()
+            .unwrap(),
+        "Invalid `length` for `truncate`"
+    );
+    assert_eq!(
+        error("{{user | upper(1)}}"),
+        (10, "`upper` takes at most 0 arguments".to_string())
+    );
+}
+
+#[test]
+fn style_codes() {
+    let styled = || Cow::Borrowed("\u{1b}[38;5;1mError\u{1b}[0m");
+    assert_eq!(UpperFilter.apply(styled()), "\u{1b}[38;5;1mERROR\u{1b}[0m");
+    assert_eq!(LowerFilter.apply(styled()), "\u{1b}[38;5;1merror\u{1b}[0m");
+    assert_eq!(
+        TruncateFilter(2).apply(styled()),
+        "\u{1b}[38;5;1mEr\u{1b}[0m"
+    );
+    assert_eq!(TruncateFilter(5).apply(styled()), styled());
+}
+
+/// Custom filters work in the path of a file target
+#[test]
+fn file_path() {
+    let directory = std::env::temp_dir().join(format!("nitro_log_filter_{}", std::process::id()));
+    let file = directory.join("{{level({}) | reverse}}.log");
+    let config: Config = serde_json::from_value(json!({
+        "root_loggers": [{
+            "targets": [{ "type": "file_logger", "properties": { "file": file.to_str().unwrap() } }]
+        }]
+    }))
+    .unwrap();
+    let mut builders = LoggerBuilders::default();
+    builders.filters.push(Box::new(ReverseFilterBuilder));
+    assert!(config.validate(&builders).is_ok());
+    let (root, loggers) = create_loggers(config, &builders).unwrap();
+    let logger = NitroLogger::new(LoggerTree::new(root, loggers), Box::new(|_| {}));
+    logger.log(
+        &Record::builder()
+            .args(format_args!("Hello"))
+            .level(Level::Info)
+            .build(),
+    );
+    logger.flush();
+    assert!(directory.join("OFNI.log").exists());
+    let _ = std::fs::remove_dir_all(&directory);
+}
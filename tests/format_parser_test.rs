@@ -0,0 +1,162 @@
+use nitro_log::config::{create_loggers, Config};
+use nitro_log::error::Error;
+use nitro_log::filter::standard_filters::Align;
+use nitro_log::format::parser::{parse, Alignment, Filter, ParseError, Settings, Token};
+use nitro_log::LoggerBuilders;
+use serde_json::json;
+
+fn placeholder(name: &str, settings: Option<Settings>, column: usize) -> Token {
+    Token::Placeholder {
+        name: name.to_string(),
+        settings,
+        alignment: None,
+        filters: vec![],
+        column,
+    }
+}
+
+#[test]
+fn tokens() {
+    assert_eq!(
+        parse("[{{ level() }}] {{user.id}}: {{message(message)}}").unwrap(),
+        vec![
+            Token::Text("[".to_string()),
+            placeholder("level", None, 2),
+            Token::Text("] ".to_string()),
+            Token::Variable {
+                name: "user.id".to_string(),
+                alignment: None,
+                filters: vec![],
+                column: 17,
+            },
+            Token::Text(": ".to_string()),
+            placeholder("message", Some(Settings::Named("message".to_string())), 30),
+        ]
+    );
+}
+
+#[test]
+fn nested_json() {
+    assert_eq!(
+        parse(r#"{{env({"key": "A)}}", "nested": {"list": [{"a": "}"}]}})}}!"#).unwrap(),
+        vec![
+            placeholder(
+                "env",
+                Some(Settings::Inline(
+                    json!({ "key": "A)}}", "nested": { "list": [{ "a": "}" }] } })
+                )),
+                1
+            ),
+            Token::Text("!".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn escapes() {
+    assert_eq!(
+        parse(r"\{{not a placeholder\}} C:\logs").unwrap(),
+        vec![Token::Text(r"{{not a placeholder}} C:\logs".to_string())]
+    );
+}
+
+#[test]
+fn blocks_and_filters() {
+    assert_eq!(
+        parse(r#"{{#if id}}{{ id | default("none", 1) | upper }}{{/if}}"#).unwrap(),
+        vec![
+            Token::If {
+                name: "id".to_string(),
+                column: 1,
+            },
+            Token::Variable {
+                name: "id".to_string(),
+                alignment: None,
+                filters: vec![
+                    Filter {
+                        name: "default".to_string(),
+                        args: vec![json!("none"), json!(1)],
+                        column: 19,
+                    },
+                    Filter {
+                        name: "upper".to_string(),
+                        args: vec![],
+                        column: 40,
+                    },
+                ],
+                column: 11,
+            },
+            Token::EndIf { column: 48 },
+        ]
+    );
+}
+
+#[test]
+fn alignment() {
+    let alignment = |format: &str| match parse(format).unwrap().remove(0) {
+        Token::Variable { alignment, .. } | Token::Placeholder { alignment, .. } => alignment,
+        token => panic!("Unexpected token {:?}", token),
+    };
+    let aligned = |fill, align, width| Some(Alignment { fill, align, width });
+    assert_eq!(alignment("{{level:>5}}"), aligned(' ', Align::Right, 5));
+    assert_eq!(alignment("{{ user.id:12 }}"), aligned(' ', Align::Left, 12));
+    assert_eq!(
+        alignment("{{module({}):*^20 | upper}}"),
+        aligned('*', Align::Center, 20)
+    );
+    assert_eq!(alignment("{{x:<<3}}"), aligned('<', Align::Left, 3));
+    assert_eq!(
+        parse("{{level:>}}").unwrap_err(),
+        ParseError {
+            column: 9,
+            message: "Invalid alignment `>`. Expected `[[fill]<, > or ^]width`".to_string(),
+        }
+    );
+    assert_eq!(parse("{{level:+5}}").unwrap_err().column, 9);
+}
+
+#[test]
+fn errors() {
+    let error = |format: &str| parse(format).unwrap_err();
+    assert_eq!(
+        error("ab {{message({})"),
+        ParseError {
+            column: 4,
+            message: "Unclosed `{{`. Expected `}}`".to_string(),
+        }
+    );
+    assert_eq!(error("{{}}").column, 3);
+    assert_eq!(error("{{#for x}}").message, "Unknown block `for`");
+    assert_eq!(error(r#"{{x | default("a" "b")}}"#).column, 19);
+    assert_eq!(error("{{message({}}}").column, 13);
+    assert_eq!(error(r#"{{message({"a" 1})}}"#).column, 16);
+    assert_eq!(
+        error(r#"{{message({"a": 1)}}"#),
+        ParseError {
+            column: 18,
+            message: "Invalid settings: expected `,` or `}`".to_string(),
+        }
+    );
+    assert_eq!(
+        error(r#"{{message({"a": 1"#).message,
+        "Unclosed settings object"
+    );
+}
+
+#[test]
+fn config_path() {
+    let config: Config = serde_json::from_value(json!({
+        "root_loggers": [{ "format": "{{message(", "targets": [] }]
+    }))
+    .unwrap();
+    match create_loggers(config, &LoggerBuilders::default())
+        .err()
+        .unwrap()
+    {
+        Error::InvalidFormat { column, path, .. } => {
+            assert_eq!(column, 1);
+            assert_eq!(path, "root_loggers[0].format");
+        }
+        error => panic!("Unexpected error {}", error),
+    }
+}
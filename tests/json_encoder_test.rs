@@ -0,0 +1,61 @@
+use log::info;
+use nitro_log::{LoggerBuilders, NitroLogger};
+use serde::Serialize;
+use serde_json::{json, Value};
+
+#[derive(Serialize)]
+pub struct User {
+    pub id: u64,
+    pub name: String,
+}
+
+#[test]
+fn test() {
+    let directory = std::env::temp_dir().join(format!("nitro_log_json_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&directory);
+    let file = directory.join("app.log");
+    let config = json!({
+        "root_loggers": [
+            {
+                "encoder": "json",
+                "targets": [
+                    {
+                        "type": "file_logger",
+                        "properties": {
+                            "file": file.to_str().unwrap()
+                        }
+                    }
+                ]
+            }
+        ]
+    });
+    NitroLogger::load(
+        serde_json::from_value(config).unwrap(),
+        LoggerBuilders::default(),
+    )
+    .unwrap();
+    let user = User {
+        id: 5,
+        name: "Wyatt".to_string(),
+    };
+    info!(request_id = 10, user:serde = user; "Hello {}", "World");
+    info!("Second \"line\"");
+
+    let content = std::fs::read_to_string(&file).unwrap();
+    let lines: Vec<Value> = content
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(lines.len(), 2);
+    let first = &lines[0];
+    assert_eq!(first["level"], "INFO");
+    assert_eq!(first["message"], "Hello World");
+    assert_eq!(first["target"], "json_encoder_test");
+    assert_eq!(first["module_path"], "json_encoder_test");
+    assert_eq!(first["request_id"], 10);
+    assert_eq!(first["user"], json!({"id": 5, "name": "Wyatt"}));
+    assert!(first["line"].is_u64());
+    assert!(first["timestamp"].as_str().unwrap().ends_with('Z'));
+    assert_eq!(lines[1]["message"], "Second \"line\"");
+    std::fs::remove_dir_all(&directory).unwrap();
+}
@@ -1,5 +1,5 @@
 use std::fs::OpenOptions;
-use log::{as_error, as_serde, debug, error, info, log_enabled, trace, warn};
+use log::{debug, error, info, log_enabled, trace, warn};
 use nitro_log::{LoggerBuilders, NitroLogger};
 
 use log::Level::Trace;
@@ -29,7 +29,7 @@ fn test() {
 
     if log_enabled!(Trace) {
         let error1 = FormatError::MissingKey("Value".to_string());
-        trace!(value = as_serde!(test), error=as_error!(error1); "Trace HEY");
+        trace!(value:serde = test, error:err = error1; "Trace HEY");
     }
     debug!("Debug Hey");
     info!("INFO HEY {}", test.hi);
@@ -0,0 +1,31 @@
+use log::LevelFilter;
+use nitro_log::{LoggerBuilders, NitroLogger};
+use serde_json::json;
+
+#[test]
+fn test() {
+    let config = json!({
+        "root_loggers": [
+            { "level": "info", "targets": [] }
+        ],
+        "loggers": [
+            { "module": "my_app::db", "level": "debug", "targets": [] }
+        ]
+    });
+    let handle = NitroLogger::load(
+        serde_json::from_value(config).unwrap(),
+        LoggerBuilders::default(),
+    )
+    .unwrap();
+    assert_eq!(log::max_level(), LevelFilter::Debug);
+
+    let config = json!({
+        "root_loggers": [
+            { "levels": ["ERROR", "WARN"], "targets": [] }
+        ]
+    });
+    handle
+        .reload(serde_json::from_value(config).unwrap())
+        .unwrap();
+    assert_eq!(log::max_level(), LevelFilter::Warn);
+}
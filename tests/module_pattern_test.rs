@@ -0,0 +1,119 @@
+use nitro_log::config::{create_loggers, Config};
+use nitro_log::error::Error;
+use nitro_log::loggers::tree::LoggerTree;
+use nitro_log::LoggerBuilders;
+use serde_json::json;
+
+fn tree(loggers: serde_json::Value) -> Result<LoggerTree, Error> {
+    let config: Config = serde_json::from_value(json!({
+        "root_loggers": [{ "module": "root", "targets": [] }],
+        "loggers": loggers
+    }))
+    .unwrap();
+    let (root, loggers) = create_loggers(config, &LoggerBuilders::default())?;
+    Ok(LoggerTree::new(root, loggers))
+}
+
+fn modules<'tree>(tree: &'tree LoggerTree, path: &str) -> Vec<&'tree str> {
+    tree.find_logger(path)
+        .unwrap()
+        .into_iter()
+        .map(|logger| logger.module.as_deref().unwrap())
+        .collect()
+}
+
+#[test]
+fn glob() {
+    let tree = tree(json!([
+        { "module": "my_app::*::db", "targets": [] },
+        { "module": "my_app::**::cache", "targets": [] }
+    ]))
+    .unwrap();
+    assert_eq!(modules(&tree, "my_app::users::db"), vec!["my_app::*::db"]);
+    assert_eq!(
+        modules(&tree, "my_app::users::db::pool"),
+        vec!["my_app::*::db"]
+    );
+    assert_eq!(modules(&tree, "my_app::users::admin::db"), vec!["root"]);
+    assert_eq!(
+        modules(&tree, "my_app::users::admin::cache"),
+        vec!["my_app::**::cache"]
+    );
+    assert_eq!(modules(&tree, "other::db"), vec!["root"]);
+}
+
+#[test]
+fn regex() {
+    let tree = tree(json!([
+        { "module": "regex:^my_app::v\\d+$", "targets": [] }
+    ]))
+    .unwrap();
+    assert_eq!(modules(&tree, "my_app::v2"), vec!["regex:^my_app::v\\d+$"]);
+    assert_eq!(modules(&tree, "my_app::v2::routes"), vec!["root"]);
+}
+
+#[test]
+fn precedence() {
+    let tree = tree(json!([
+        { "module": "my_app::**", "targets": [] },
+        { "module": "my_app::*::db", "targets": [] },
+        { "module": "my_app::users::db", "targets": [] }
+    ]))
+    .unwrap();
+    // A literal module wins over patterns
+    assert_eq!(
+        modules(&tree, "my_app::users::db"),
+        vec!["my_app::users::db"]
+    );
+    // Every matching pattern is used in config order
+    assert_eq!(
+        modules(&tree, "my_app::posts::db"),
+        vec!["my_app::**", "my_app::*::db"]
+    );
+}
+
+#[test]
+fn regex_is_anchored() {
+    let tree = tree(json!([
+        { "module": "regex:my_app::v\\d+", "targets": [] }
+    ]))
+    .unwrap();
+    assert_eq!(modules(&tree, "my_app::v2"), vec!["regex:my_app::v\\d+"]);
+    assert_eq!(modules(&tree, "other::my_app::v2"), vec!["root"]);
+    assert_eq!(modules(&tree, "my_app::v2::routes"), vec!["root"]);
+}
+
+#[test]
+fn literal_parent_and_pattern_child() {
+    let tree = tree(json!([
+        { "module": "my_app", "targets": [] },
+        { "module": "my_app::*::db", "additive": true, "targets": [] },
+        { "module": "my_app::users::db", "targets": [] }
+    ]))
+    .unwrap();
+    // The pattern is more specific than the literal parent
+    assert_eq!(
+        modules(&tree, "my_app::posts::db"),
+        vec!["my_app::*::db", "my_app", "root"]
+    );
+    // A literal at the same depth wins
+    assert_eq!(
+        modules(&tree, "my_app::users::db"),
+        vec!["my_app::users::db"]
+    );
+    // The pattern does not match so the literal parent is used
+    assert_eq!(modules(&tree, "my_app::posts"), vec!["my_app"]);
+}
+
+#[test]
+fn invalid() {
+    let error = tree(json!([
+        { "module": "regex:my_app::(", "targets": [] }
+    ]))
+    .err()
+    .unwrap();
+    assert!(matches!(
+        &error,
+        Error::InvalidModulePattern { path, .. } if path == "loggers[0].module"
+    ));
+}
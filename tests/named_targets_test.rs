@@ -0,0 +1,82 @@
+use std::sync::Arc;
+
+use nitro_log::config::{create_loggers, Config};
+use nitro_log::error::Error;
+use nitro_log::loggers::tree::LoggerTree;
+use nitro_log::LoggerBuilders;
+use serde_json::json;
+
+#[test]
+fn shared() {
+    let file = std::env::temp_dir().join(format!("nitro_log_named_{}.log", std::process::id()));
+    let config: Config = serde_json::from_value(json!({
+        "targets": {
+            "app_file": { "type": "file_logger", "properties": { "file": file.to_str().unwrap() } }
+        },
+        "root_loggers": [
+            { "format": "{{message({})}}", "targets": ["app_file", { "type": "console" }] }
+        ],
+        "loggers": [
+            { "module": "my_app::db", "format": "db {{message({})}}", "targets": ["app_file"] }
+        ]
+    }))
+    .unwrap();
+    assert!(config.validate(&LoggerBuilders::default()).is_ok());
+    let (root, loggers) = create_loggers(config, &LoggerBuilders::default()).unwrap();
+    let tree = LoggerTree::new(root, loggers);
+    let root = tree.find_logger("my_app").unwrap()[0];
+    let db = tree.find_logger("my_app::db").unwrap()[0];
+    assert_eq!(root.targets.len(), 2);
+    assert!(Arc::ptr_eq(&root.targets[0].target, &db.targets[0].target));
+    let _ = std::fs::remove_file(&file);
+}
+
+#[test]
+fn undefined() {
+    let config: Config = serde_json::from_value(json!({
+        "targets": { "console": { "type": "console" } },
+        "root_loggers": [{ "targets": ["console", "app_file"] }]
+    }))
+    .unwrap();
+    let problems = config.validate(&LoggerBuilders::default()).unwrap_err();
+    assert_eq!(problems.len(), 1);
+    match create_loggers(config, &LoggerBuilders::default())
+        .err()
+        .unwrap()
+    {
+        Error::UndefinedTarget { name, known, path } => {
+            assert_eq!(name, "app_file");
+            assert_eq!(known, vec!["console".to_string()]);
+            assert_eq!(path, "root_loggers[0].targets[1]");
+        }
+        error => panic!("Unexpected error {}", error),
+    }
+}
+
+#[test]
+fn invalid_named() {
+    let config: Config = serde_json::from_value(json!({
+        "targets": { "broken": { "type": "missing" } },
+        "root_loggers": []
+    }))
+    .unwrap();
+    let problems = config.validate(&LoggerBuilders::default()).unwrap_err();
+    assert_eq!(problems[0].logger, "targets.broken");
+    match create_loggers(config, &LoggerBuilders::default())
+        .err()
+        .unwrap()
+    {
+        Error::UnknownTarget { path, .. } => assert_eq!(path, "targets.broken.type"),
+        error => panic!("Unexpected error {}", error),
+    }
+}
+
+#[test]
+fn invalid_inline_target() {
+    let error = serde_json::from_value::<Config>(json!({
+        "root_loggers": [{ "targets": [{ "type": "console", "level": "loud" }] }]
+    }))
+    .err()
+    .unwrap();
+    assert!(error.to_string().contains("loud"), "{}", error);
+}
@@ -0,0 +1,91 @@
+use std::fs::File;
+use std::path::Path;
+use std::time::{Duration, Instant, SystemTime};
+
+use log::info;
+use nitro_log::config::Config;
+use nitro_log::{LoggerBuilders, NitroLogger};
+use serde_json::json;
+
+fn config(log_file: &Path, format: &str) -> serde_json::Value {
+    json!({
+        "root_loggers": [
+            {
+                "format": format,
+                "targets": [
+                    {
+                        "type": "file_logger",
+                        "properties": {
+                            "file": log_file.to_str().unwrap()
+                        }
+                    }
+                ]
+            }
+        ]
+    })
+}
+
+#[test]
+fn test() {
+    let directory = std::env::temp_dir().join(format!("nitro_log_reload_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&directory);
+    std::fs::create_dir_all(&directory).unwrap();
+    let log_file = directory.join("app.log");
+    let handle = NitroLogger::load(
+        serde_json::from_value(config(&log_file, "A {{message({})}}")).unwrap(),
+        LoggerBuilders::default(),
+    )
+    .unwrap();
+    info!("First");
+
+    let reloaded: Config = serde_json::from_value(config(&log_file, "B {{message({})}}")).unwrap();
+    handle.reload(reloaded).unwrap();
+    info!("Second");
+
+    // A broken config keeps the current loggers
+    let broken: Config = serde_json::from_value(config(&log_file, "C {{missing({})}}")).unwrap();
+    assert!(handle.reload(broken).is_err());
+    info!("Third");
+    assert_eq!(
+        std::fs::read_to_string(&log_file).unwrap(),
+        "A First\nB Second\nB Third\n"
+    );
+
+    let config_file = directory.join("config.json");
+    std::fs::write(
+        &config_file,
+        config(&log_file, "B {{message({})}}").to_string(),
+    )
+    .unwrap();
+    let watcher = handle
+        .watch(&config_file, Duration::from_millis(10))
+        .unwrap();
+    std::fs::write(
+        &config_file,
+        config(&log_file, "D {{message({})}}").to_string(),
+    )
+    .unwrap();
+    // Make sure the modified time changes on file systems with a coarse resolution
+    File::options()
+        .write(true)
+        .open(&config_file)
+        .unwrap()
+        .set_modified(SystemTime::now() + Duration::from_secs(5))
+        .unwrap();
+    let started = Instant::now();
+    while started.elapsed() < Duration::from_secs(10) {
+        info!("Watched");
+        if std::fs::read_to_string(&log_file)
+            .unwrap()
+            .ends_with("D Watched\n")
+        {
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    watcher.stop();
+    assert!(std::fs::read_to_string(&log_file)
+        .unwrap()
+        .ends_with("D Watched\n"));
+    std::fs::remove_dir_all(&directory).unwrap();
+}
@@ -0,0 +1,73 @@
+mod common;
+
+use common::{RecordingBuilder, Writes};
+use log::{Level, Log, Record};
+use nitro_log::config::create_loggers;
+use nitro_log::loggers::tree::LoggerTree;
+use nitro_log::{LoggerBuilders, NitroLogger};
+use serde_json::{json, Value};
+
+fn log(logger: Value) -> (Vec<String>, Vec<String>) {
+    let (first, second) = (Writes::default(), Writes::default());
+    let mut builders = LoggerBuilders::default();
+    builders
+        .targets
+        .push(Box::new(RecordingBuilder::new(&[&first, &second])));
+    let config = json!({ "root_loggers": [logger] });
+    let (root, loggers) =
+        create_loggers(serde_json::from_value(config).unwrap(), &builders).unwrap();
+    let logger = NitroLogger::new(LoggerTree::new(root, loggers), Box::new(|_| {}));
+    let kvs = [("user", "admin")];
+    logger.log(
+        &Record::builder()
+            .args(format_args!("Hello"))
+            .level(Level::Info)
+            .module_path(Some("my_app"))
+            .key_values(&kvs)
+            .build(),
+    );
+    let writes = |writes: Writes| writes.lock().unwrap().clone();
+    (writes(first), writes(second))
+}
+
+#[test]
+fn single_write() {
+    let (first, second) = log(json!({
+        "format": "[{{level({}):>5}}] {{#if user}}{{user}}: {{/if}}{{message({})}}",
+        "structure_dump": true,
+        "targets": [
+            { "type": "recording" },
+            { "type": "recording", "properties": { "output": 1 } }
+        ]
+    }));
+    assert_eq!(first, vec!["[ INFO] admin: Hello\nuser: admin\n"]);
+    assert_eq!(second, first);
+}
+
+#[test]
+fn own_format() {
+    let (first, second) = log(json!({
+        "format": "{{message({})}}",
+        "targets": [
+            { "type": "recording" },
+            {
+                "type": "recording",
+                "properties": { "output": 1 },
+                "format": "{{level({})}} {{message({})}}"
+            }
+        ]
+    }));
+    assert_eq!(first, vec!["Hello\n"]);
+    assert_eq!(second, vec!["INFO Hello\n"]);
+}
+
+#[test]
+fn json() {
+    let (first, _) = log(json!({
+        "encoder": "json",
+        "targets": [{ "type": "recording" }]
+    }));
+    assert_eq!(first.len(), 1);
+    assert!(first[0].starts_with('{'));
+    assert!(first[0].ends_with("}\n"));
+}
@@ -0,0 +1,43 @@
+use log::info;
+use nitro_log::{LoggerBuilders, NitroLogger};
+use serde_json::json;
+
+#[test]
+fn test() {
+    let directory = std::env::temp_dir().join(format!("nitro_log_rolling_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&directory);
+    let file = directory.join("app.log");
+    let config = json!({
+        "root_loggers": [
+            {
+                "format": "{{level({})}}: {{message({})}}",
+                "targets": [
+                    {
+                        "type": "file_logger",
+                        "properties": {
+                            "file": file.to_str().unwrap(),
+                            "rolling": { "max_size": 1, "max_files": 2 }
+                        }
+                    }
+                ]
+            }
+        ]
+    });
+    NitroLogger::load(
+        serde_json::from_value(config).unwrap(),
+        LoggerBuilders::default(),
+    )
+    .unwrap();
+    for i in 0..5 {
+        info!("Message {}", i);
+    }
+    assert!(file.exists());
+    assert!(directory.join("app.log.1").exists());
+    assert!(directory.join("app.log.2").exists());
+    assert!(!directory.join("app.log.3").exists());
+    let newest = std::fs::read_to_string(&file).unwrap();
+    assert!(newest.contains("Message 4"));
+    let archived = std::fs::read_to_string(directory.join("app.log.1")).unwrap();
+    assert!(archived.contains("Message 3"));
+    std::fs::remove_dir_all(&directory).unwrap();
+}
@@ -0,0 +1,94 @@
+#![cfg(feature = "style-term")]
+mod common;
+
+use log::{Level, Record};
+use nitro_log::error::Error;
+use nitro_log::LoggerBuilders;
+
+fn render(format: &str) -> Result<String, Error> {
+    let format = common::format(&LoggerBuilders::default(), format)?;
+    let kvs = [("user", "admin")];
+    let record = Record::builder()
+        .args(format_args!("Hello"))
+        .level(Level::Info)
+        .module_path(Some("my_app::db"))
+        .key_values(&kvs)
+        .build();
+    Ok(common::render(&format, &record))
+}
+
+#[test]
+fn placeholder() {
+    assert_eq!(
+        render(r#"{{module({"style": {"text_color": "cyan"}})}}: {{message({})}}"#).unwrap(),
+        "\u{1b}[38;5;6mmy_app::db\u{1b}[0m: Hello"
+    );
+}
+
+#[test]
+fn text_and_variable() {
+    assert_eq!(
+        render(r#"{{text({"value": " | ", "style": {"styles": [1]}})}}{{user | style({"background_color": "bright_red"})}}"#)
+            .unwrap(),
+        "\u{1b}[1m | \u{1b}[0m\u{1b}[48;5;9madmin\u{1b}[0m"
+    );
+}
+
+#[test]
+fn invalid() {
+    match render(r#"{{module({"style": {"text_color": "ultraviolet"}})}}"#).unwrap_err() {
+        Error::InvalidPlaceholderSettings { path, message, .. } => {
+            assert_eq!(path, "style");
+            assert_eq!(message, "Unknown color `ultraviolet`");
+        }
+        error => panic!("Unexpected error {}", error),
+    }
+    assert!(matches!(
+        render(r#"{{missing({"style": {}, "other": 1})}}"#).unwrap_err(),
+        Error::UnknownPlaceholder { .. }
+    ));
+    // A typo in a placeholder name is not a styled variable
+    assert!(matches!(
+        render(r#"{{modle({"style": {"text_color": "cyan"}})}}"#).unwrap_err(),
+        Error::UnknownPlaceholder { .. }
+    ));
+    let error = |format: &str| match render(format).unwrap_err() {
+        Error::InvalidFormat { message, .. } => message,
+        error => panic!("Unexpected error {}", error),
+    };
+    assert_eq!(
+        error(r#"{{user | style({}) | upper}}"#),
+        "`style` must be the last filter"
+    );
+    assert_eq!(error("{{user | style}}"), "`style` takes one argument");
+    assert_eq!(
+        error(r#"{{user | style({"text_color": "ultraviolet"})}}"#),
+        "Unknown color `ultraviolet`"
+    );
+}
+
+#[test]
+fn filter() {
+    assert_eq!(
+        render(r#"{{user:>6 | style({"styles": [1]})}}"#).unwrap(),
+        "\u{1b}[1m admin\u{1b}[0m"
+    );
+    assert_eq!(
+        render(r#"{{module() | style({"text_color": "cyan"})}}"#).unwrap(),
+        "\u{1b}[38;5;6mmy_app::db\u{1b}[0m"
+    );
+}
+
+#[test]
+fn filters_keep_codes() {
+    let plain = render(r#"{{level({"styles": {}})}}"#).unwrap();
+    assert!(plain.contains("Info\u{1b}"));
+    assert_eq!(
+        render(r#"{{level({"styles": {}}) | upper}}"#).unwrap(),
+        plain.replace("Info", "INFO")
+    );
+    assert_eq!(
+        render(r#"{{level({"styles": {}}) | truncate(2)}}"#).unwrap(),
+        plain.replace("Info", "In")
+    );
+}
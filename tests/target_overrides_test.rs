@@ -0,0 +1,40 @@
+use std::path::Path;
+
+use log::{debug, info};
+use nitro_log::{LoggerBuilders, NitroLogger};
+use serde_json::json;
+
+#[test]
+fn test() {
+    let directory =
+        std::env::temp_dir().join(format!("nitro_log_target_overrides_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&directory);
+    let (verbose, plain) = (directory.join("verbose.log"), directory.join("plain.log"));
+    let config = json!({
+        "root_loggers": [{
+            "format": "{{level({})}} {{message({})}}",
+            "targets": [
+                { "type": "file_logger", "properties": { "file": verbose.to_str().unwrap() } },
+                {
+                    "type": "file_logger",
+                    "properties": { "file": plain.to_str().unwrap() },
+                    "format": "{{message({})}}",
+                    "level": "info"
+                }
+            ]
+        }]
+    });
+    NitroLogger::load(
+        serde_json::from_value(config).unwrap(),
+        LoggerBuilders::default(),
+    )
+    .unwrap();
+    assert!(log::log_enabled!(log::Level::Trace));
+    debug!("Debug");
+    info!("Info");
+
+    let read = |file: &Path| std::fs::read_to_string(file).unwrap();
+    assert_eq!(read(&verbose), "DEBUG Debug\nINFO Info\n");
+    assert_eq!(read(&plain), "Info\n");
+    std::fs::remove_dir_all(&directory).unwrap();
+}
@@ -0,0 +1,52 @@
+use std::path::Path;
+
+use log::{info, Level, Record};
+use nitro_log::{LoggerBuilders, NitroLogger};
+use serde_json::json;
+
+fn file_target(file: &Path) -> serde_json::Value {
+    json!([{ "type": "file_logger", "properties": { "file": file.to_str().unwrap() } }])
+}
+
+#[test]
+fn test() {
+    let directory = std::env::temp_dir().join(format!("nitro_log_target_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&directory);
+    let (root, audit, metrics) = (
+        directory.join("root.log"),
+        directory.join("audit.log"),
+        directory.join("metrics.log"),
+    );
+    let config = json!({
+        "root_loggers": [
+            { "format": "{{message({})}}", "targets": file_target(&root) }
+        ],
+        "loggers": [
+            { "target": "audit", "format": "{{message({})}}", "targets": file_target(&audit) },
+            { "target": "metrics*", "format": "{{message({})}}", "targets": file_target(&metrics) }
+        ]
+    });
+    NitroLogger::load(
+        serde_json::from_value(config).unwrap(),
+        LoggerBuilders::default(),
+    )
+    .unwrap();
+    info!("Root");
+    info!(target: "audit", "Audit");
+    info!(target: "audit::login", "Not Audit");
+    info!(target: "metrics::http", "Metrics");
+    // Records from bridges may not have a module path
+    log::logger().log(
+        &Record::builder()
+            .args(format_args!("No Module"))
+            .level(Level::Info)
+            .target("bridge")
+            .build(),
+    );
+
+    let read = |file: &Path| std::fs::read_to_string(file).unwrap();
+    assert_eq!(read(&root), "Root\nNot Audit\nNo Module\n");
+    assert_eq!(read(&audit), "Audit\n");
+    assert_eq!(read(&metrics), "Metrics\n");
+    std::fs::remove_dir_all(&directory).unwrap();
+}